@@ -0,0 +1,460 @@
+//! Walks over the Pandoc JSON AST used by the build's filter.
+
+use serde_json::{Value, json};
+
+/// Adjusts media URLs so pandoc hashes them via `--extract-media`.
+/// Adds a redundant `../` segment to force pandoc to treat the path as non-original.
+fn bump_media_path(url: &mut String) {
+	if url.starts_with('#')
+		|| url.starts_with("http://")
+		|| url.starts_with("https://")
+		|| url.starts_with("data:")
+		|| url.contains("..")
+	{
+		return;
+	}
+	let bumped = match url.trim_start_matches("./").split_once('/') {
+		Some((first, rest)) => format!("{first}/../{first}/{rest}"),
+		None => format!("../{url}"),
+	};
+	*url = bumped;
+}
+
+/// Escapes `&`, `<`, `>` and `"` for use in HTML text and attribute values.
+pub fn escape_html(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+/// A heading collected from the Pandoc AST for a page's table of contents.
+pub struct TocEntry {
+	pub level: u64,
+	pub id: String,
+	pub text: String,
+}
+
+/// Appends the plain text of Pandoc inline elements to `text`.
+pub fn inline_text(inlines: &Value, text: &mut String) {
+	let Some(inlines) = inlines.as_array() else {
+		return;
+	};
+	for inline in inlines {
+		let content = inline.get("c");
+		match inline.get("t").and_then(Value::as_str) {
+			Some("Str") => text.push_str(content.and_then(Value::as_str).unwrap_or_default()),
+			Some("Space" | "SoftBreak" | "LineBreak") => text.push(' '),
+			Some("Code" | "Math") => {
+				let code = content.and_then(|c| c.get(1)).and_then(Value::as_str);
+				text.push_str(code.unwrap_or_default());
+			}
+			Some(
+				"Emph" | "Underline" | "Strong" | "Strikeout" | "Superscript" | "Subscript"
+				| "SmallCaps",
+			) => {
+				if let Some(content) = content {
+					inline_text(content, text);
+				}
+			}
+			Some("Quoted" | "Cite" | "Link" | "Image" | "Span") => {
+				if let Some(content) = content.and_then(|c| c.get(1)) {
+					inline_text(content, text);
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Classes of a Pandoc `[id, classes, attributes]` triple.
+pub fn classes(attr: &Value) -> Vec<&str> {
+	attr.get(1)
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+		.filter_map(Value::as_str)
+		.collect()
+}
+
+/// Value of the attribute `key` in a Pandoc `[id, classes, attributes]` triple.
+pub fn attribute<'a>(attr: &'a Value, key: &str) -> Option<&'a str> {
+	attr.get(2)?
+		.as_array()?
+		.iter()
+		.find_map(|pair| (pair.get(0)?.as_str()? == key).then(|| pair.get(1)?.as_str())?)
+}
+
+/// Id, classes, attributes and content blocks of a `Div`; other blocks yield `None`.
+pub fn div_parts(block: &Value) -> Option<(&str, Vec<&str>, &Value, &Vec<Value>)> {
+	if block.get("t").and_then(Value::as_str) != Some("Div") {
+		return None;
+	}
+	let content = block.get("c")?;
+	let attr = content.get(0)?;
+	let id = attr.get(0).and_then(Value::as_str).unwrap_or_default();
+	Some((id, classes(attr), attr, content.get(1)?.as_array()?))
+}
+
+/// A `RawBlock` of HTML.
+pub fn raw_block(html: String) -> Value {
+	json!({ "t": "RawBlock", "c": ["html", html] })
+}
+
+/// A `RawInline` of HTML.
+pub fn raw_inline(html: String) -> Value {
+	json!({ "t": "RawInline", "c": ["html", html] })
+}
+
+/// Renders a Pandoc `[id, classes, attributes]` triple as HTML attributes, each preceded
+/// by a space.
+fn attr_html(attr: &Value) -> String {
+	let mut html = String::new();
+	if let Some(id) = attr
+		.get(0)
+		.and_then(Value::as_str)
+		.filter(|id| !id.is_empty())
+	{
+		html.push_str(&format!(" id=\"{}\"", escape_html(id)));
+	}
+	let classes = classes(attr);
+	if !classes.is_empty() {
+		html.push_str(&format!(" class=\"{}\"", escape_html(&classes.join(" "))));
+	}
+	for pair in attr.get(2).and_then(Value::as_array).into_iter().flatten() {
+		if let (Some(key), Some(value)) = (
+			pair.get(0).and_then(Value::as_str),
+			pair.get(1).and_then(Value::as_str),
+		) {
+			html.push_str(&format!(" {}=\"{}\"", escape_html(key), escape_html(value)));
+		}
+	}
+	html
+}
+
+/// Pandoc elements that [`inline_html`] and [`blocks_html`] cannot render, by the name
+/// used in messages.
+const UNRENDERED: &[(&str, &str)] = &[
+	("Image", "an image"),
+	("Note", "a footnote"),
+	("Table", "a table"),
+	("Figure", "a figure"),
+	("LineBlock", "a line block"),
+	("DefinitionList", "a definition list"),
+];
+
+/// Names the elements in `value` that [`inline_html`] and [`blocks_html`] would drop, each
+/// once, in document order.
+pub fn unrendered_elements(value: &Value) -> Vec<&'static str> {
+	fn collect(value: &Value, found: &mut Vec<&'static str>) {
+		match value {
+			Value::Array(items) => {
+				for item in items {
+					collect(item, found);
+				}
+			}
+			Value::Object(map) => {
+				let kind = map.get("t").and_then(Value::as_str);
+				if let Some((_, name)) = UNRENDERED.iter().find(|(t, _)| Some(*t) == kind)
+					&& !found.contains(name)
+				{
+					found.push(name);
+				}
+				if let Some(content) = map.get("c") {
+					collect(content, found);
+				}
+			}
+			_ => {}
+		}
+	}
+	let mut found = Vec::new();
+	collect(value, &mut found);
+	found
+}
+
+/// Renders Pandoc inline elements as HTML, for content shown outside the page body.
+/// Covers the formatting found in short texts; see [`unrendered_elements`] for the
+/// elements it leaves out.
+pub fn inline_html(inlines: &Value) -> String {
+	let mut html = String::new();
+	for inline in inlines.as_array().into_iter().flatten() {
+		let content = inline.get("c");
+		let text = |index: usize| {
+			content
+				.and_then(|c| c.get(index))
+				.and_then(Value::as_str)
+				.unwrap_or_default()
+		};
+		let wrap = |tag: &str, inlines: Option<&Value>| {
+			format!(
+				"<{tag}>{}</{tag}>",
+				inlines.map(inline_html).unwrap_or_default()
+			)
+		};
+		match inline.get("t").and_then(Value::as_str) {
+			Some("Str") => html.push_str(&escape_html(
+				content.and_then(Value::as_str).unwrap_or_default(),
+			)),
+			Some("Space" | "SoftBreak") => html.push(' '),
+			Some("LineBreak") => html.push_str("<br>"),
+			Some("Code") => html.push_str(&format!("<code>{}</code>", escape_html(text(1)))),
+			Some("Math") => html.push_str(&format!(
+				"<code class=\"math\">{}</code>",
+				escape_html(text(1))
+			)),
+			Some("RawInline") if text(0) == "html" => html.push_str(text(1)),
+			Some("Emph") => html.push_str(&wrap("em", content)),
+			Some("Strong") => html.push_str(&wrap("strong", content)),
+			Some("Underline") => html.push_str(&wrap("u", content)),
+			Some("Strikeout") => html.push_str(&wrap("del", content)),
+			Some("Superscript") => html.push_str(&wrap("sup", content)),
+			Some("Subscript") => html.push_str(&wrap("sub", content)),
+			Some("SmallCaps") => html.push_str(&content.map(inline_html).unwrap_or_default()),
+			Some("Quoted") => {
+				let double = content
+					.and_then(|c| c.get(0))
+					.and_then(|kind| kind.get("t"))
+					.and_then(Value::as_str)
+					== Some("DoubleQuote");
+				let (open, close) = if double {
+					("“", "”")
+				} else {
+					("‘", "’")
+				};
+				let quoted = content.and_then(|c| c.get(1)).map(inline_html);
+				html.push_str(&format!("{open}{}{close}", quoted.unwrap_or_default()));
+			}
+			Some("Link") => {
+				let target = content
+					.and_then(|c| c.get(2))
+					.and_then(|target| target.get(0))
+					.and_then(Value::as_str)
+					.unwrap_or_default();
+				let label = content.and_then(|c| c.get(1)).map(inline_html);
+				html.push_str(&format!(
+					"<a href=\"{}\">{}</a>",
+					escape_html(target),
+					label.unwrap_or_default()
+				));
+			}
+			Some("Span") => {
+				let attr = content.and_then(|c| c.get(0)).map(attr_html);
+				let inlines = content.and_then(|c| c.get(1)).map(inline_html);
+				html.push_str(&format!(
+					"<span{}>{}</span>",
+					attr.unwrap_or_default(),
+					inlines.unwrap_or_default()
+				));
+			}
+			Some("Cite") => {
+				if let Some(inlines) = content.and_then(|c| c.get(1)) {
+					html.push_str(&inline_html(inlines));
+				}
+			}
+			_ => {}
+		}
+	}
+	html
+}
+
+/// Renders Pandoc blocks as HTML, like [`inline_html`]. Raw HTML blocks, such as
+/// highlighted code, are kept as they are.
+pub fn blocks_html(blocks: &Value) -> String {
+	let mut html = String::new();
+	for block in blocks.as_array().into_iter().flatten() {
+		let content = block.get("c");
+		let list = |tag: &str, items: Option<&Value>| {
+			let items = items
+				.and_then(Value::as_array)
+				.into_iter()
+				.flatten()
+				.map(|item| format!("<li>{}</li>", blocks_html(item)))
+				.collect::<String>();
+			format!("<{tag}>{items}</{tag}>")
+		};
+		match block.get("t").and_then(Value::as_str) {
+			Some("Plain") => html.push_str(&content.map(inline_html).unwrap_or_default()),
+			Some("Para") => html.push_str(&format!(
+				"<p>{}</p>",
+				content.map(inline_html).unwrap_or_default()
+			)),
+			Some("RawBlock")
+				if content.and_then(|c| c.get(0)).and_then(Value::as_str) == Some("html") =>
+			{
+				let raw = content.and_then(|c| c.get(1)).and_then(Value::as_str);
+				html.push_str(raw.unwrap_or_default());
+			}
+			Some("CodeBlock") => {
+				let code = content.and_then(|c| c.get(1)).and_then(Value::as_str);
+				html.push_str(&format!(
+					"<pre><code>{}</code></pre>",
+					escape_html(code.unwrap_or_default())
+				));
+			}
+			Some("BulletList") => html.push_str(&list("ul", content)),
+			Some("OrderedList") => html.push_str(&list("ol", content.and_then(|c| c.get(1)))),
+			Some("BlockQuote") => html.push_str(&format!(
+				"<blockquote>{}</blockquote>",
+				content.map(blocks_html).unwrap_or_default()
+			)),
+			Some("Div") => {
+				let attr = content.and_then(|c| c.get(0)).map(attr_html);
+				let blocks = content.and_then(|c| c.get(1)).map(blocks_html);
+				html.push_str(&format!(
+					"<div{}>{}</div>",
+					attr.unwrap_or_default(),
+					blocks.unwrap_or_default()
+				));
+			}
+			Some("HorizontalRule") => html.push_str("<hr>"),
+			Some("Header") => {
+				let mut text = String::new();
+				if let Some(inlines) = content.and_then(|c| c.get(2)) {
+					inline_text(inlines, &mut text);
+				}
+				html.push_str(&format!("<p><strong>{}</strong></p>", escape_html(&text)));
+			}
+			_ => {}
+		}
+	}
+	html
+}
+
+/// Collects headings from a Pandoc JSON document in document order.
+/// Headings without an id or marked `.unlisted` are skipped.
+pub fn collect_headings(value: &Value, toc: &mut Vec<TocEntry>) {
+	match value {
+		Value::Array(items) => {
+			for item in items {
+				collect_headings(item, toc);
+			}
+		}
+		Value::Object(map) => {
+			let content = map.get("c");
+			match map.get("t").and_then(Value::as_str) {
+				Some("Header") => {
+					let level = content.and_then(|c| c.get(0)).and_then(Value::as_u64);
+					let attr = content.and_then(|c| c.get(1));
+					let id = attr.and_then(|a| a.get(0)).and_then(Value::as_str);
+					let unlisted = attr
+						.and_then(|a| a.get(1))
+						.and_then(Value::as_array)
+						.is_some_and(|classes| classes.iter().any(|c| c == "unlisted"));
+					if !unlisted
+						&& let (Some(level), Some(id)) = (level, id.filter(|id| !id.is_empty()))
+					{
+						let mut text = String::new();
+						if let Some(inlines) = content.and_then(|c| c.get(2)) {
+							inline_text(inlines, &mut text);
+						}
+						toc.push(TocEntry {
+							level,
+							id: id.to_string(),
+							text,
+						});
+					}
+				}
+				// Footnote content never contains document headings.
+				Some("Note") => {}
+				_ => {
+					if let Some(content) = content {
+						collect_headings(content, toc);
+					}
+				}
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Appends a "¶" link to its own fragment to every heading with an id.
+pub fn add_heading_anchors(value: &mut Value) {
+	match value {
+		Value::Array(items) => {
+			for item in items {
+				add_heading_anchors(item);
+			}
+		}
+		Value::Object(map) => {
+			if map.get("t").and_then(Value::as_str) == Some("Header") {
+				let Some(Value::Array(content)) = map.get_mut("c") else {
+					return;
+				};
+				let id = content
+					.get(1)
+					.and_then(|attr| attr.get(0))
+					.and_then(Value::as_str)
+					.filter(|id| !id.is_empty())
+					.map(escape_html);
+				if let (Some(id), Some(Value::Array(inlines))) = (id, content.get_mut(2)) {
+					let anchor = format!(
+						"<a class=\"heading-anchor\" href=\"#{id}\" aria-label=\"Link to this section\">¶</a>"
+					);
+					inlines.push(json!({ "t": "Space" }));
+					inlines.push(raw_inline(anchor));
+				}
+				return;
+			}
+			for (_, v) in map.iter_mut() {
+				add_heading_anchors(v);
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Points a relative link to a Markdown file at the page's route, keeping any `#fragment`.
+/// `page_route` maps the file path as written to a route; unknown files are left as is.
+fn rewrite_page_link(url: &mut String, page_route: &mut dyn FnMut(&str) -> Option<String>) {
+	if url.starts_with('#') || url.starts_with('/') || url.contains("://") {
+		return;
+	}
+	let (path, fragment) = url.split_at(url.find('#').unwrap_or(url.len()));
+	if !path.ends_with(".md") {
+		return;
+	}
+	if let Some(route) = page_route(path) {
+		*url = format!("{route}{fragment}");
+	}
+}
+
+/// Recursively rewrites image/link targets in a Pandoc JSON document: images for media
+/// extraction and links to Markdown files through `page_route`.
+pub fn rewrite_links(value: &mut Value, page_route: &mut dyn FnMut(&str) -> Option<String>) {
+	match value {
+		Value::Array(items) => {
+			let kind = items.first().and_then(Value::as_str);
+			let is_image = kind == Some("Image");
+			if (is_image || kind == Some("Link"))
+				&& let Some(Value::Array(target)) = items.get_mut(3)
+				&& let Some(Value::String(url)) = target.get_mut(0)
+			{
+				if is_image {
+					bump_media_path(url);
+				} else {
+					rewrite_page_link(url, page_route);
+				}
+			}
+			for item in items {
+				rewrite_links(item, page_route);
+			}
+		}
+		Value::Object(map) => {
+			let kind = map.get("t").and_then(Value::as_str).map(str::to_string);
+			if let Some(Value::Array(c)) = map.get_mut("c")
+				&& let Some(Value::Array(target)) = c.get_mut(2)
+				&& let Some(Value::String(url)) = target.get_mut(0)
+			{
+				match kind.as_deref() {
+					Some("Image") => bump_media_path(url),
+					Some("Link") => rewrite_page_link(url, page_route),
+					_ => {}
+				}
+			}
+			for (_, v) in map.iter_mut() {
+				rewrite_links(v, page_route);
+			}
+		}
+		_ => {}
+	}
+}
@@ -0,0 +1,169 @@
+//! Callout blocks from fenced divs, such as `::: note` or `::: {.warning title="Careful"}`.
+//!
+//! A div whose classes name a callout kind gets a header with the kind's icon and title
+//! (overridden by a `title` attribute). Adding `.details` makes it collapsible, closed
+//! unless it also has `.open`; `::: {.details title="..."}` on its own is a plain
+//! collapsible block.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::ast::{attribute, div_parts, escape_html, raw_block};
+
+/// Class marking a callout as collapsible; also a kind of its own.
+const DETAILS: &str = "details";
+
+/// Built-in kinds as `(kind, title, icon)`; their colours are in `styles.scss`.
+const BUILT_IN: &[(&str, &str, &str)] = &[
+	("note", "Note", "ℹ"),
+	("info", "Info", "ℹ"),
+	("tip", "Tip", "💡"),
+	("success", "Success", "✓"),
+	("warning", "Warning", "⚠"),
+	("danger", "Danger", "✕"),
+	("example", "Example", "✎"),
+	(DETAILS, "Details", "⋯"),
+];
+
+/// A callout kind declared or restyled under `[callouts.<kind>]` in bezel.toml.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalloutConfig {
+	/// Header shown when the callout sets no `title`; defaults to the capitalised kind.
+	pub title: Option<String>,
+	/// Emoji or short text shown before the title.
+	pub icon: Option<String>,
+	/// CSS colour of the border, header and tinted background.
+	pub color: Option<String>,
+}
+
+struct CalloutKind {
+	title: String,
+	icon: String,
+	color: Option<String>,
+}
+
+/// The callout kinds of a site: the built-in ones plus those from the config.
+pub struct Callouts(BTreeMap<String, CalloutKind>);
+
+impl Callouts {
+	pub fn new(custom: &BTreeMap<String, CalloutConfig>) -> Self {
+		let mut kinds: BTreeMap<String, CalloutKind> = BUILT_IN
+			.iter()
+			.map(|(name, title, icon)| {
+				let kind = CalloutKind {
+					title: title.to_string(),
+					icon: icon.to_string(),
+					color: None,
+				};
+				(name.to_string(), kind)
+			})
+			.collect();
+		for (name, config) in custom {
+			let kind = kinds.entry(name.clone()).or_insert_with(|| CalloutKind {
+				title: capitalize(name),
+				icon: "•".into(),
+				color: None,
+			});
+			if let Some(title) = &config.title {
+				kind.title = title.clone();
+			}
+			if let Some(icon) = &config.icon {
+				kind.icon = icon.clone();
+			}
+			if config.color.is_some() {
+				kind.color = config.color.clone();
+			}
+		}
+		Self(kinds)
+	}
+
+	/// Replaces callout divs in a Pandoc JSON document with their titled HTML structure.
+	pub fn transform(&self, value: &mut Value) {
+		match value {
+			Value::Array(items) => {
+				let mut transformed = Vec::with_capacity(items.len());
+				for mut item in items.drain(..) {
+					self.transform(&mut item);
+					match self.expand(&item) {
+						Some(blocks) => transformed.extend(blocks),
+						None => transformed.push(item),
+					}
+				}
+				*items = transformed;
+			}
+			Value::Object(map) => {
+				for (_, v) in map.iter_mut() {
+					self.transform(v);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Expands a callout `Div` (`[[id, classes, attributes], blocks]`) into raw HTML around
+	/// its blocks; other blocks yield `None`.
+	fn expand(&self, block: &Value) -> Option<Vec<Value>> {
+		let (id, classes, attr, content) = div_parts(block)?;
+
+		let collapsible = classes.contains(&DETAILS);
+		let name = classes
+			.iter()
+			.copied()
+			.find(|class| *class != DETAILS && self.0.contains_key(*class))
+			.or_else(|| classes.contains(&"callout").then_some("note"))
+			.or_else(|| collapsible.then_some(DETAILS))?;
+		let kind = &self.0[name];
+		let open = classes.contains(&"open") || attribute(attr, "open").is_some();
+		let title = attribute(attr, "title").unwrap_or(&kind.title);
+
+		let mut class = format!("callout {name}");
+		for extra in classes
+			.iter()
+			.filter(|class| !matches!(**class, "callout" | DETAILS | "open") && **class != name)
+		{
+			class.push(' ');
+			class.push_str(extra);
+		}
+		let (tag, header_tag) = if collapsible {
+			("details", "summary")
+		} else {
+			("div", "div")
+		};
+		let mut html = format!("<{tag} class=\"{}\"", escape_html(&class));
+		if !id.is_empty() {
+			html.push_str(&format!(" id=\"{}\"", escape_html(id)));
+		}
+		if let Some(color) = &kind.color {
+			html.push_str(&format!(
+				" style=\"--callout-color: {}\"",
+				escape_html(color)
+			));
+		}
+		if collapsible && open {
+			html.push_str(" open");
+		}
+		html.push_str(&format!(
+			"><{header_tag} class=\"callout-title\"><span class=\"callout-icon\" aria-hidden=\"true\">{}</span><span>{}</span></{header_tag}><div class=\"callout-body\">",
+			escape_html(&kind.icon),
+			escape_html(title)
+		));
+
+		let mut blocks = vec![raw_block(html)];
+		blocks.extend(content.iter().cloned());
+		blocks.push(raw_block(format!("</div></{tag}>")));
+		Some(blocks)
+	}
+}
+
+/// `"key-idea"` → `"Key idea"`.
+fn capitalize(kind: &str) -> String {
+	let text = kind.replace(['-', '_'], " ");
+	let mut chars = text.chars();
+	chars
+		.next()
+		.map(|first| first.to_uppercase().chain(chars).collect())
+		.unwrap_or_default()
+}
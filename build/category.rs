@@ -0,0 +1,129 @@
+//! Category metadata from `_category.yml` files and the `[categories]` config section.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+use crate::diagnostics::Diagnostics;
+
+/// Metadata file describing the category of the folder it sits in.
+pub const CATEGORY_FILE: &str = "_category.yml";
+
+/// Definition of the generated `CategoryMeta` type, emitted ahead of the category tables.
+pub const CATEGORY_STRUCT: &str = r#"
+pub struct CategoryMeta {
+	pub name: Option<&'static str>,
+	pub order: Option<u32>,
+	pub description: Option<&'static str>,
+	pub icon: Option<&'static str>,
+	pub collapsed: bool,
+}
+"#;
+
+/// Display settings of a sidebar category; every key is optional.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryMeta {
+	/// Name shown instead of the frontmatter category or the folder's index title.
+	pub name: Option<String>,
+	pub order: Option<u32>,
+	pub description: Option<String>,
+	/// Emoji or short text shown before the name.
+	pub icon: Option<String>,
+	/// Whether the category starts collapsed in the sidebar.
+	#[serde(default)]
+	pub collapsed: bool,
+}
+
+impl CategoryMeta {
+	fn to_rust(&self) -> String {
+		let CategoryMeta {
+			name,
+			order,
+			description,
+			icon,
+			collapsed,
+		} = self;
+		format!(
+			"CategoryMeta {{ name: {name:?}, order: {order:?}, description: {description:?}, icon: {icon:?}, collapsed: {collapsed} }}"
+		)
+	}
+}
+
+/// Renders `(key, metadata)` pairs as a Rust constant named `name`.
+pub fn categories_rust<'a>(
+	name: &str,
+	categories: impl IntoIterator<Item = (&'a String, &'a CategoryMeta)>,
+) -> String {
+	let mut code = format!("pub const {name}: &[(&str, CategoryMeta)] = &[\n");
+	for (key, meta) in categories {
+		code.push_str(&format!("    ({key:?}, {}),\n", meta.to_rust()));
+	}
+	code.push_str("];\n");
+	code
+}
+
+/// Reads every [`CATEGORY_FILE`] under the content roots, keyed by the folder's slug.
+pub fn collect_category_files(
+	roots: &[String],
+	diagnostics: &mut Diagnostics,
+) -> Vec<(String, CategoryMeta)> {
+	let mut categories = Vec::new();
+
+	for root in roots {
+		let root = Path::new(root);
+		for entry in WalkDir::new(root)
+			.sort_by_file_name()
+			.into_iter()
+			.filter_map(Result::ok)
+			.filter(|e| e.file_name() == CATEGORY_FILE)
+		{
+			let path = entry.path();
+			let folder = path.parent().unwrap_or(root);
+			let slug = module_slug(root, folder);
+			if slug.is_empty() {
+				diagnostics.warning(
+					path,
+					1,
+					"content roots have no category of their own; describe top-level \
+					 categories under `[categories]` in bezel.toml instead",
+				);
+				continue;
+			}
+
+			let text = fs::read_to_string(path).expect("read category file");
+			if text.trim().is_empty() {
+				categories.push((slug, CategoryMeta::default()));
+				continue;
+			}
+			match serde_yaml::from_str::<CategoryMeta>(&text) {
+				Ok(meta) => categories.push((slug, meta)),
+				Err(err) => {
+					let line = err.location().map_or(1, |location| location.line());
+					diagnostics.error(path, line, format!("invalid category metadata: {err}"));
+				}
+			}
+		}
+	}
+
+	categories
+}
+
+/// Derives a page slug from a module folder's path relative to its content root.
+/// Nested folders keep their full path, e.g. `rust/ownership/borrowing`.
+pub fn module_slug(root: &Path, module_dir: &Path) -> String {
+	module_dir
+		.strip_prefix(root)
+		.expect("module folder inside modules dir")
+		.components()
+		.map(|component| {
+			component
+				.as_os_str()
+				.to_str()
+				.expect("utf8 module folder name")
+		})
+		.collect::<Vec<_>>()
+		.join("/")
+}
@@ -0,0 +1,275 @@
+//! Site configuration read from `bezel.toml` at the crate root.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use pandoc::PandocOption;
+use serde::Deserialize;
+
+use crate::callout::CalloutConfig;
+use crate::category::CategoryMeta;
+use crate::diagnostics::Diagnostics;
+use crate::highlight::theme_names;
+
+pub const CONFIG_FILE: &str = "bezel.toml";
+
+/// Contents of `bezel.toml`; every section and key is optional.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+	pub site: SiteConfig,
+	pub content: ContentConfig,
+	pub markdown: MarkdownConfig,
+	pub assets: AssetsConfig,
+	/// Metadata of top-level categories, keyed by the frontmatter `category` name.
+	pub categories: BTreeMap<String, CategoryMeta>,
+	/// Custom callout kinds and overrides of built-in ones, keyed by the div class.
+	pub callouts: BTreeMap<String, CalloutConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteConfig {
+	/// Site name shown in document titles and Open Graph tags.
+	pub title: String,
+	/// Document title template; `{title}` is the page title and `{site}` the site title.
+	pub title_template: String,
+	/// Public URL of the deployed site, used for canonical links.
+	pub url: Option<String>,
+}
+
+impl Default for SiteConfig {
+	fn default() -> Self {
+		Self {
+			title: "Bezel".into(),
+			title_template: "{title} | {site}".into(),
+			url: None,
+		}
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContentConfig {
+	/// Folders scanned for Markdown modules, relative to the crate root.
+	pub roots: Vec<String>,
+	/// Category of pages that set none and whose module index sets none either.
+	pub default_category: String,
+	/// Order of pages that set none.
+	pub default_order: u32,
+	/// Top-level sidebar categories listed first, in this order; others follow alphabetically.
+	pub category_order: Vec<String>,
+}
+
+impl Default for ContentConfig {
+	fn default() -> Self {
+		Self {
+			roots: vec!["resources/modules".into()],
+			default_category: "Uncategorized".into(),
+			default_order: 999,
+			category_order: Vec::new(),
+		}
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarkdownConfig {
+	/// Pandoc Markdown extensions to enable, by their Pandoc names.
+	pub extensions: Vec<String>,
+	pub math: MathEngine,
+	pub highlight: HighlightConfig,
+}
+
+impl Default for MarkdownConfig {
+	fn default() -> Self {
+		Self {
+			extensions: [
+				"fenced_divs",
+				"bracketed_spans",
+				"fenced_code_attributes",
+				"implicit_figures",
+				"raw_html",
+				"footnotes",
+				"task_lists",
+				"pipe_tables",
+				"smart",
+			]
+			.map(String::from)
+			.to_vec(),
+			math: MathEngine::default(),
+			highlight: HighlightConfig::default(),
+		}
+	}
+}
+
+/// Syntax highlighting of fenced code blocks.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HighlightConfig {
+	/// Bundled syntect theme used with the light colour theme.
+	pub light_theme: String,
+	/// Bundled syntect theme used with the dark colour theme.
+	pub dark_theme: String,
+	/// Number the lines of every code block, not only those marked `.numberLines`.
+	pub line_numbers: bool,
+}
+
+impl Default for HighlightConfig {
+	fn default() -> Self {
+		Self {
+			light_theme: "InspiredGitHub".into(),
+			dark_theme: "base16-ocean.dark".into(),
+			line_numbers: false,
+		}
+	}
+}
+
+/// How Pandoc renders TeX math.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MathEngine {
+	/// Markup for a MathJax script, which the app does not load.
+	MathJax,
+	/// Static MathML rendered by the browser; needs no script or network.
+	#[default]
+	MathMl,
+	/// Markup for a KaTeX script, which the app does not load.
+	Katex,
+	/// Leave math as plain text.
+	None,
+}
+
+impl MathEngine {
+	pub fn pandoc_option(self) -> Option<PandocOption> {
+		match self {
+			MathEngine::MathJax => Some(PandocOption::MathJax(None)),
+			MathEngine::MathMl => Some(PandocOption::MathML(None)),
+			MathEngine::Katex => Some(PandocOption::Katex(None)),
+			MathEngine::None => None,
+		}
+	}
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AssetsConfig {
+	/// URL prefix of extracted media, `/assets/` by default.
+	/// Offline builds (`BEZEL_OFFLINE`) always use `./assets/`.
+	pub base_url: Option<String>,
+}
+
+/// Returns the 1-based line of byte `offset` in `text`.
+fn line_of(text: &str, offset: usize) -> usize {
+	text[..offset.min(text.len())].lines().count().max(1)
+}
+
+impl Config {
+	/// Reads [`CONFIG_FILE`], falling back to defaults if it is missing.
+	/// Problems are recorded in `diagnostics` and also fall back to defaults.
+	pub fn load(diagnostics: &mut Diagnostics) -> Self {
+		let path = Path::new(CONFIG_FILE);
+		let Ok(text) = fs::read_to_string(path) else {
+			return Self::default();
+		};
+		let config: Self = match toml::from_str(&text) {
+			Ok(config) => config,
+			Err(err) => {
+				let line = err.span().map_or(1, |span| line_of(&text, span.start));
+				diagnostics.error(path, line, format!("invalid config: {}", err.message()));
+				return Self::default();
+			}
+		};
+
+		for extension in &config.markdown.extensions {
+			if extension.is_empty()
+				|| !extension
+					.chars()
+					.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+			{
+				let line = text
+					.find(&format!("\"{extension}\""))
+					.map_or(1, |offset| line_of(&text, offset));
+				diagnostics.error(
+					path,
+					line,
+					format!("`{extension}` is not a Pandoc extension name, e.g. `fenced_divs`"),
+				);
+			}
+		}
+
+		for kind in config.callouts.keys() {
+			if kind.is_empty()
+				|| !kind
+					.chars()
+					.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+			{
+				let line = text
+					.find(&format!("callouts.{kind}"))
+					.or_else(|| text.find(&format!("callouts.\"{kind}\"")))
+					.map_or(1, |offset| line_of(&text, offset));
+				diagnostics.error(
+					path,
+					line,
+					format!(
+						"callout kind `{kind}` must be lowercase letters, digits and `-`, as it is \
+						 used as a class"
+					),
+				);
+			}
+		}
+
+		let script_engine = match config.markdown.math {
+			MathEngine::MathJax => Some("\"mathjax\""),
+			MathEngine::Katex => Some("\"katex\""),
+			_ => None,
+		};
+		if let Some(value) = script_engine {
+			let line = text.find(value).map_or(1, |offset| line_of(&text, offset));
+			diagnostics.warning(
+				path,
+				line,
+				"this math engine needs a script the app does not load, so math shows as TeX \
+				 source; use `math = \"mathml\"` to render it at build time",
+			);
+		}
+
+		let themes = theme_names();
+		let highlight = &config.markdown.highlight;
+		for theme in [&highlight.light_theme, &highlight.dark_theme] {
+			if !themes.contains(theme) {
+				let line = text
+					.find(&format!("\"{theme}\""))
+					.map_or(1, |offset| line_of(&text, offset));
+				diagnostics.error(
+					path,
+					line,
+					format!(
+						"unknown highlight theme `{theme}`; available: {}",
+						themes.join(", ")
+					),
+				);
+			}
+		}
+		config
+	}
+
+	/// Renders the settings the app needs as Rust source for `src/site.rs`.
+	pub fn site_rust(&self) -> String {
+		let SiteConfig {
+			title,
+			title_template,
+			url,
+		} = &self.site;
+		let category_order = &self.content.category_order;
+		let default_category = &self.content.default_category;
+		format!(
+			"pub const SITE_NAME: &str = {title:?};\n\
+			 pub const TITLE_TEMPLATE: &str = {title_template:?};\n\
+			 pub const SITE_URL: Option<&str> = {url:?};\n\
+			 pub const CATEGORY_ORDER: &[&str] = &{category_order:?};\n\
+			 pub const DEFAULT_CATEGORY: &str = {default_category:?};\n"
+		)
+	}
+}
@@ -0,0 +1,172 @@
+//! Collection and reporting of content problems found by the build.
+
+use std::path::{Path, PathBuf};
+
+/// Severity of a content problem found while scanning modules or the site config.
+#[derive(PartialEq)]
+enum Severity {
+	Warning,
+	Error,
+}
+
+/// A content problem pointing at a file and line.
+struct Diagnostic {
+	severity: Severity,
+	path: PathBuf,
+	line: usize,
+	message: String,
+}
+
+/// Collects content problems across the site config and every content root.
+#[derive(Default)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+	fn push(&mut self, severity: Severity, path: &Path, line: usize, message: String) {
+		self.0.push(Diagnostic {
+			severity,
+			path: path.to_path_buf(),
+			line,
+			message,
+		});
+	}
+
+	pub fn warning(&mut self, path: &Path, line: usize, message: impl Into<String>) {
+		self.push(Severity::Warning, path, line, message.into());
+	}
+
+	pub fn error(&mut self, path: &Path, line: usize, message: impl Into<String>) {
+		self.push(Severity::Error, path, line, message.into());
+	}
+
+	/// Emits warnings through cargo and fails the build with a single report if any errors
+	/// were collected.
+	pub fn finish(self) {
+		let (errors, warnings): (Vec<_>, Vec<_>) = self
+			.0
+			.into_iter()
+			.partition(|diagnostic| diagnostic.severity == Severity::Error);
+
+		for warning in &warnings {
+			println!(
+				"cargo:warning={}:{}: {}",
+				warning.path.display(),
+				warning.line,
+				warning.message
+			);
+		}
+
+		if errors.is_empty() {
+			return;
+		}
+
+		eprintln!("error: found {} content problem(s):", errors.len());
+		for error in &errors {
+			eprintln!(
+				"  {}:{}: {}",
+				error.path.display(),
+				error.line,
+				error.message
+			);
+		}
+		std::process::exit(1);
+	}
+}
+
+/// Attribute tokens on a Markdown line: those of each `{...}` block, and the bare class of
+/// a `::: class` fenced div as `.class`.
+fn attribute_groups(line: &str) -> Vec<Vec<String>> {
+	if let Some(rest) = line.trim_start().strip_prefix(":::") {
+		let rest = rest.trim_start_matches(':').trim();
+		if !rest.starts_with('{') {
+			let class = rest.trim_end_matches([':', ' ']);
+			return if class.is_empty() {
+				Vec::new()
+			} else {
+				vec![vec![format!(".{class}")]]
+			};
+		}
+	}
+	line.split('{')
+		.skip(1)
+		.filter_map(|part| part.split_once('}'))
+		.map(|(group, _)| group.split_whitespace().map(str::to_string).collect())
+		.collect()
+}
+
+/// Returns the 1-based line of the `occurrence`-th (from 0) attribute block in `markdown`
+/// with a token matching `is_match`, such as `.quiz` or `hl_lines="2"`, or line 1 if there
+/// are fewer such blocks.
+pub fn attribute_line(markdown: &str, occurrence: usize, is_match: impl Fn(&str) -> bool) -> usize {
+	markdown
+		.lines()
+		.enumerate()
+		.flat_map(|(index, line)| {
+			attribute_groups(line)
+				.into_iter()
+				.map(move |group| (index, group))
+		})
+		.filter(|(_, group)| group.iter().any(|token| is_match(token)))
+		.nth(occurrence)
+		.map_or(1, |(index, _)| index + 1)
+}
+
+#[cfg(test)]
+impl Diagnostics {
+	fn collected(&self, severity: Severity) -> Vec<(usize, &str)> {
+		self.0
+			.iter()
+			.filter(|diagnostic| diagnostic.severity == severity)
+			.map(|diagnostic| (diagnostic.line, diagnostic.message.as_str()))
+			.collect()
+	}
+
+	/// Lines and messages of the errors collected so far.
+	pub fn errors(&self) -> Vec<(usize, &str)> {
+		self.collected(Severity::Error)
+	}
+
+	/// Lines and messages of the warnings collected so far.
+	pub fn warnings(&self) -> Vec<(usize, &str)> {
+		self.collected(Severity::Warning)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MARKDOWN: &str = "# Title
+
+::: {.quiz #first}
+Text with []{.component name=\"Badge\"} inline.
+:::
+
+::: quiz
+:::
+
+```{.rust hl_lines=\"2\"}
+let a = 1;
+```
+";
+
+	#[test]
+	fn finds_each_occurrence_of_a_class() {
+		let is_quiz = |token: &str| token == ".quiz";
+		assert_eq!(attribute_line(MARKDOWN, 0, is_quiz), 3);
+		assert_eq!(attribute_line(MARKDOWN, 1, is_quiz), 7);
+		assert_eq!(attribute_line(MARKDOWN, 2, is_quiz), 1);
+		assert_eq!(
+			attribute_line(MARKDOWN, 0, |token| token == ".component"),
+			4
+		);
+	}
+
+	#[test]
+	fn finds_attributes_by_key() {
+		assert_eq!(
+			attribute_line(MARKDOWN, 0, |token| token.starts_with("hl_lines=")),
+			10
+		);
+	}
+}
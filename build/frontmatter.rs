@@ -0,0 +1,329 @@
+//! YAML frontmatter parsing and validation.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::diagnostics::Diagnostics;
+
+/// Frontmatter keys accepted at the top of a Markdown file.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Frontmatter {
+	pub title: Option<String>,
+	pub category: Option<String>,
+	pub order: Option<u32>,
+	/// Short summary used for meta descriptions and listings.
+	pub description: Option<String>,
+	#[serde(default)]
+	pub tags: Vec<String>,
+	#[serde(default)]
+	pub authors: Vec<String>,
+	#[serde(default)]
+	pub draft: bool,
+	/// Publication date as `YYYY-MM-DD`, optionally followed by a time.
+	pub date: Option<String>,
+	/// Last update date, same format as `date`.
+	pub updated: Option<String>,
+	/// Extra slugs that resolve to this page, e.g. after a module was renamed.
+	#[serde(default)]
+	pub aliases: Vec<String>,
+	/// Slug of the page linked as "Previous", overriding the navigation order.
+	pub prev: Option<String>,
+	/// Slug of the page linked as "Next", overriding the navigation order.
+	pub next: Option<String>,
+	/// Free-form values passed through to the app untouched.
+	#[serde(default)]
+	pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Formats a YAML error, shifting its frontmatter-relative positions to file lines.
+fn yaml_error_message(err: &serde_yaml::Error) -> String {
+	let message = err.to_string();
+	let mut shifted = String::with_capacity(message.len());
+	let mut rest = message.as_str();
+	while let Some(idx) = rest.find("line ") {
+		shifted.push_str(&rest[..idx + 5]);
+		rest = &rest[idx + 5..];
+		let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+		if let Ok(line) = rest[..digits].parse::<usize>() {
+			shifted.push_str(&(line + 1).to_string());
+			rest = &rest[digits..];
+		}
+	}
+	shifted.push_str(rest);
+	shifted
+}
+
+/// Returns the index of the frontmatter line defining top-level `key`.
+fn key_index(yaml_lines: &[&str], key: &str) -> Option<usize> {
+	yaml_lines.iter().position(|line| {
+		line.strip_prefix(key)
+			.is_some_and(|rest| rest.starts_with(':'))
+	})
+}
+
+/// Returns the file line of top-level frontmatter `key` in `content`, or the first
+/// frontmatter line if it is not found.
+pub fn key_line(content: &str, key: &str) -> usize {
+	let yaml_lines: Vec<&str> = content
+		.lines()
+		.skip(1)
+		.take_while(|line| *line != "---")
+		.collect();
+	key_index(&yaml_lines, key).map_or(2, |index| index + 2)
+}
+
+/// Whether `value` starts with a valid `YYYY-MM-DD` date, optionally followed by a time.
+fn is_iso_date(value: &str) -> bool {
+	let bytes = value.as_bytes();
+	let digits = |range: std::ops::Range<usize>| {
+		bytes
+			.get(range.clone())
+			.is_some_and(|part| part.iter().all(u8::is_ascii_digit))
+			.then(|| value[range].parse::<u32>().unwrap_or_default())
+	};
+	let (Some(_), Some(month), Some(day)) = (digits(0..4), digits(5..7), digits(8..10)) else {
+		return false;
+	};
+	bytes[4] == b'-'
+		&& bytes[7] == b'-'
+		&& (1..=12).contains(&month)
+		&& (1..=31).contains(&day)
+		&& matches!(bytes.get(10), None | Some(b'T' | b' '))
+}
+
+/// Flattens an `extra` frontmatter value into a string; collections become JSON.
+pub fn extra_value(value: &serde_yaml::Value) -> String {
+	match value {
+		serde_yaml::Value::Null => String::new(),
+		serde_yaml::Value::Bool(value) => value.to_string(),
+		serde_yaml::Value::Number(value) => value.to_string(),
+		serde_yaml::Value::String(value) => value.clone(),
+		value => serde_json::to_string(value).unwrap_or_default(),
+	}
+}
+
+/// Extracts YAML frontmatter from markdown content.
+/// Returns (frontmatter, remaining_content), recording problems in `diagnostics`.
+/// With `strict` set, unknown keys are errors instead of warnings.
+pub fn extract_frontmatter<'a>(
+	content: &'a str,
+	path: &Path,
+	strict: bool,
+	diagnostics: &mut Diagnostics,
+) -> (Frontmatter, &'a str) {
+	let Some(rest) = content.strip_prefix("---\n") else {
+		return (Frontmatter::default(), content);
+	};
+	let (yaml_str, remaining) = if let Some(remaining) = rest.strip_prefix("---\n") {
+		("", remaining)
+	} else if let Some(end_idx) = rest.find("\n---\n") {
+		(&rest[..end_idx], &rest[end_idx + 5..])
+	} else if let Some(yaml_str) = rest.strip_suffix("\n---") {
+		(yaml_str, "")
+	} else {
+		diagnostics.error(path, 1, "frontmatter is missing its closing `---` line");
+		return (Frontmatter::default(), content);
+	};
+
+	// Frontmatter starts on the second line of the file.
+	let yaml_lines: Vec<&str> = yaml_str.lines().collect();
+	let file_line = |yaml_line: usize| yaml_line + 1;
+	let key_file_line = |key: &str| key_index(&yaml_lines, key).map_or(2, |index| index + 2);
+
+	let mut yaml = match serde_yaml::from_str::<serde_yaml::Value>(yaml_str) {
+		Ok(serde_yaml::Value::Null) => return (Frontmatter::default(), remaining),
+		Ok(serde_yaml::Value::Mapping(yaml)) => yaml,
+		Ok(_) => {
+			diagnostics.error(path, 2, "frontmatter must be a mapping of keys to values");
+			return (Frontmatter::default(), remaining);
+		}
+		Err(err) => {
+			let line = err
+				.location()
+				.map_or(2, |location| file_line(location.line()));
+			diagnostics.error(
+				path,
+				line,
+				format!("invalid frontmatter YAML: {}", yaml_error_message(&err)),
+			);
+			return (Frontmatter::default(), remaining);
+		}
+	};
+
+	// Each key is deserialized on its own, so unknown keys, rejected by
+	// `deny_unknown_fields`, and values of the wrong type are reported one by one and
+	// dropped, keeping the rest of the frontmatter.
+	yaml.retain(|key, value| {
+		let Some(key) = key.as_str() else {
+			let key = serde_yaml::to_string(key).unwrap_or_default();
+			diagnostics.error(
+				path,
+				2,
+				format!(
+					"frontmatter keys must be strings, found `{}`",
+					key.trim_end()
+				),
+			);
+			return false;
+		};
+		let single = serde_yaml::Mapping::from_iter([(key.into(), value.clone())]);
+		let Err(err) = serde_yaml::from_value::<Frontmatter>(single.into()) else {
+			return true;
+		};
+		let line = key_file_line(key);
+		// serde words unknown keys as "unknown field `key`, expected one of ...".
+		match err.to_string().strip_prefix("unknown field ") {
+			Some(rest) => {
+				let message = format!("unknown frontmatter key {rest}");
+				if strict {
+					diagnostics.error(path, line, message);
+				} else {
+					diagnostics.warning(path, line, message);
+				}
+			}
+			None => diagnostics.error(path, line, format!("invalid frontmatter `{key}`: {err}")),
+		}
+		false
+	});
+
+	let frontmatter = match serde_yaml::from_value::<Frontmatter>(yaml.into()) {
+		Ok(frontmatter) => frontmatter,
+		Err(err) => {
+			diagnostics.error(path, 2, format!("invalid frontmatter: {err}"));
+			return (Frontmatter::default(), remaining);
+		}
+	};
+	for (key, date) in [
+		("date", &frontmatter.date),
+		("updated", &frontmatter.updated),
+	] {
+		if let Some(date) = date.as_deref().filter(|date| !is_iso_date(date)) {
+			diagnostics.error(
+				path,
+				key_file_line(key),
+				format!("`{key}` must be a `YYYY-MM-DD` date, found `{date}`"),
+			);
+		}
+	}
+	(frontmatter, remaining)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(content: &str, strict: bool) -> (Frontmatter, String, Diagnostics) {
+		let mut diagnostics = Diagnostics::default();
+		let (frontmatter, body) =
+			extract_frontmatter(content, Path::new("page.md"), strict, &mut diagnostics);
+		(frontmatter, body.to_string(), diagnostics)
+	}
+
+	#[test]
+	fn parses_known_keys() {
+		let content = "---\ntitle: Borrowing\norder: 3\ntags: [rust, memory]\ndate: 2024-05-01\nextra:\n  level: 2\n---\n# Body\n";
+		let (frontmatter, body, diagnostics) = parse(content, false);
+		assert_eq!(frontmatter.title.as_deref(), Some("Borrowing"));
+		assert_eq!(frontmatter.order, Some(3));
+		assert_eq!(frontmatter.tags, ["rust", "memory"]);
+		assert_eq!(frontmatter.date.as_deref(), Some("2024-05-01"));
+		assert_eq!(extra_value(&frontmatter.extra["level"]), "2");
+		assert_eq!(body, "# Body\n");
+		assert!(diagnostics.errors().is_empty() && diagnostics.warnings().is_empty());
+	}
+
+	#[test]
+	fn content_without_frontmatter_is_untouched() {
+		let (frontmatter, body, diagnostics) = parse("# Title\n", false);
+		assert!(frontmatter.title.is_none());
+		assert_eq!(body, "# Title\n");
+		assert!(diagnostics.errors().is_empty());
+	}
+
+	#[test]
+	fn unknown_keys_warn_and_keep_the_rest() {
+		let content = "---\ntitle: Borrowing\nsubtitle:\n  - nested\norder: 3\n---\n";
+		let (frontmatter, _, diagnostics) = parse(content, false);
+		assert_eq!(frontmatter.title.as_deref(), Some("Borrowing"));
+		assert_eq!(frontmatter.order, Some(3));
+		let [(line, message)] = diagnostics.warnings()[..] else {
+			panic!("expected one warning");
+		};
+		assert_eq!(line, 3);
+		assert!(message.starts_with("unknown frontmatter key `subtitle`, expected one of `title`"));
+		assert!(diagnostics.errors().is_empty());
+	}
+
+	#[test]
+	fn unknown_keys_are_errors_in_strict_mode() {
+		let (frontmatter, _, diagnostics) = parse("---\ntitle: A\nsubtitle: B\n---\n", true);
+		assert_eq!(frontmatter.title.as_deref(), Some("A"));
+		assert_eq!(diagnostics.errors().len(), 1);
+		assert_eq!(diagnostics.errors()[0].0, 3);
+		assert!(diagnostics.warnings().is_empty());
+	}
+
+	#[test]
+	fn invalid_values_are_reported_at_their_key() {
+		let content = "---\ntitle: Borrowing\norder: first\n---\n";
+		let (frontmatter, _, diagnostics) = parse(content, false);
+		assert_eq!(frontmatter.title.as_deref(), Some("Borrowing"));
+		assert_eq!(frontmatter.order, None);
+		let [(line, message)] = diagnostics.errors()[..] else {
+			panic!("expected one error");
+		};
+		assert_eq!(line, 3);
+		assert!(message.starts_with("invalid frontmatter `order`"));
+	}
+
+	#[test]
+	fn invalid_dates_are_reported() {
+		let content = "---\ntitle: A\ndate: 2024-05-01\nupdated: May 2024\n---\n";
+		let (_, _, diagnostics) = parse(content, false);
+		assert_eq!(
+			diagnostics.errors(),
+			[(4, "`updated` must be a `YYYY-MM-DD` date, found `May 2024`")]
+		);
+	}
+
+	#[test]
+	fn yaml_errors_point_at_file_lines() {
+		let (_, _, diagnostics) = parse("---\ntitle: A\ntags: [a\n---\n", false);
+		let errors = diagnostics.errors();
+		assert_eq!(errors.len(), 1);
+		assert!(errors[0].1.starts_with("invalid frontmatter YAML"));
+		assert!(errors[0].0 >= 3);
+	}
+
+	#[test]
+	fn missing_closing_line_is_an_error() {
+		let (_, body, diagnostics) = parse("---\ntitle: A\n# Body\n", false);
+		assert_eq!(body, "---\ntitle: A\n# Body\n");
+		assert_eq!(
+			diagnostics.errors(),
+			[(1, "frontmatter is missing its closing `---` line")]
+		);
+	}
+
+	#[test]
+	fn iso_dates() {
+		assert!(is_iso_date("2024-05-01"));
+		assert!(is_iso_date("2024-05-01T10:30:00Z"));
+		assert!(is_iso_date("2024-05-01 10:30"));
+		assert!(!is_iso_date("2024-5-1"));
+		assert!(!is_iso_date("2024-13-01"));
+		assert!(!is_iso_date("2024-05-00"));
+		assert!(!is_iso_date("2024-05-01x"));
+		assert!(!is_iso_date("May 2024"));
+	}
+
+	#[test]
+	fn key_lines() {
+		let content = "---\ntitle: A\nprev: intro\n---\n";
+		assert_eq!(key_line(content, "prev"), 3);
+		assert_eq!(key_line(content, "next"), 2);
+	}
+}
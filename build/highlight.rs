@@ -0,0 +1,329 @@
+//! Build-time syntax highlighting of fenced code blocks with syntect.
+//!
+//! Code blocks are replaced by raw HTML whose tokens carry `syn-` scope classes; the
+//! colours come from [`Highlighter::css`], which scopes a light and a dark theme to the
+//! app's `data-theme` attribute.
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{ClassStyle, css_for_theme_with_class_style, line_tokens_to_classed_spans};
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxSet};
+
+use crate::ast::{attribute, escape_html, raw_block};
+use crate::config::HighlightConfig;
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "syn-" };
+
+/// Names of the bundled themes usable as `light_theme`/`dark_theme`.
+pub fn theme_names() -> Vec<String> {
+	ThemeSet::load_defaults().themes.into_keys().collect()
+}
+
+/// Opening tag of the span syntect emits for `scope`.
+fn scope_span(scope: Scope) -> String {
+	let classes: Vec<String> = scope
+		.build_string()
+		.split('.')
+		.map(|atom| format!("syn-{atom}"))
+		.collect();
+	format!("<span class=\"{}\">", classes.join(" "))
+}
+
+/// Parses `hl_lines` such as `"3-5,8"` or `"3-5 8"` into 1-based line numbers of a block
+/// of `line_count` lines. Ranges running past the last line are clamped to it; problems,
+/// including malformed and reversed parts, are described in the returned messages.
+fn parse_line_ranges(spec: &str, line_count: usize) -> (BTreeSet<usize>, Vec<String>) {
+	let mut lines = BTreeSet::new();
+	let mut problems = Vec::new();
+	for part in spec.split([',', ' ']).filter(|part| !part.is_empty()) {
+		let (start, end) = part.split_once('-').unwrap_or((part, part));
+		let (Ok(start), Ok(end)) = (start.trim().parse::<usize>(), end.trim().parse::<usize>())
+		else {
+			problems.push(format!(
+				"`hl_lines` part `{part}` is not a line number or a range such as `3-5`"
+			));
+			continue;
+		};
+		if start == 0 {
+			problems.push(format!("`hl_lines` part `{part}` starts before line 1"));
+		} else if start > end {
+			problems.push(format!("`hl_lines` range `{part}` is reversed"));
+		} else if start > line_count {
+			problems.push(format!(
+				"`hl_lines` part `{part}` is past the end of the {line_count}-line block"
+			));
+		} else {
+			if end > line_count {
+				problems.push(format!(
+					"`hl_lines` range `{part}` runs past the end of the {line_count}-line block"
+				));
+			}
+			lines.extend(start..=end.min(line_count));
+		}
+	}
+	(lines, problems)
+}
+
+/// Highlights code blocks with the configured syntaxes and themes.
+pub struct Highlighter {
+	syntaxes: SyntaxSet,
+	light: Theme,
+	dark: Theme,
+	line_numbers: bool,
+}
+
+impl Highlighter {
+	/// Loads the bundled syntaxes and the configured themes, which [`crate::config::Config::load`]
+	/// has already checked against [`theme_names`].
+	pub fn new(config: &HighlightConfig) -> Self {
+		let mut themes = ThemeSet::load_defaults().themes;
+		let mut theme = |name: &str| themes.remove(name).unwrap_or_default();
+		Self {
+			syntaxes: SyntaxSet::load_defaults_newlines(),
+			light: theme(&config.light_theme),
+			dark: theme(&config.dark_theme),
+			line_numbers: config.line_numbers,
+		}
+	}
+
+	/// Token colours for both themes; dark rules apply under `data-theme="dark"`.
+	pub fn css(&self) -> String {
+		let mut css = String::new();
+		for (theme, selector) in [
+			(&self.light, ":root:not([data-theme=\"dark\"])"),
+			(&self.dark, ":root[data-theme=\"dark\"]"),
+		] {
+			let rules = css_for_theme_with_class_style(theme, CLASS_STYLE).expect("theme css");
+			css.push_str(&scope_css(&rules, selector));
+		}
+		css
+	}
+
+	/// Replaces every `CodeBlock` in a Pandoc JSON document with highlighted HTML.
+	///
+	/// Problems with `hl_lines` are collected in `problems`, each with the index of its code
+	/// block among those that have `hl_lines`, in document order.
+	pub fn highlight_code_blocks(&self, value: &mut Value, problems: &mut Vec<(usize, String)>) {
+		let mut emphasised_blocks = 0;
+		self.highlight_value(value, &mut emphasised_blocks, problems);
+	}
+
+	fn highlight_value(
+		&self,
+		value: &mut Value,
+		emphasised_blocks: &mut usize,
+		problems: &mut Vec<(usize, String)>,
+	) {
+		match value {
+			Value::Array(items) => {
+				for item in items {
+					self.highlight_value(item, emphasised_blocks, problems);
+				}
+			}
+			Value::Object(map) => {
+				if map.get("t").and_then(Value::as_str) == Some("CodeBlock") {
+					let Some(content) = map.get("c") else {
+						return;
+					};
+					let has_ranges = content
+						.get(0)
+						.and_then(|attr| attribute(attr, "hl_lines"))
+						.is_some();
+					let mut block_problems = Vec::new();
+					if let Some(html) = self.code_block_html(content, &mut block_problems) {
+						*value = raw_block(html);
+					}
+					problems.extend(
+						block_problems
+							.into_iter()
+							.map(|problem| (*emphasised_blocks, problem)),
+					);
+					if has_ranges {
+						*emphasised_blocks += 1;
+					}
+					return;
+				}
+				for (_, v) in map.iter_mut() {
+					self.highlight_value(v, emphasised_blocks, problems);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Renders the content of a `CodeBlock`: `[[id, classes, attributes], code]`.
+	///
+	/// The first class names the language. `.numberLines` (or `line_numbers` in the config)
+	/// numbers the lines, starting from `startFrom`; `hl_lines` emphasises lines of the block
+	/// and `title` names the file shown above it.
+	fn code_block_html(&self, content: &Value, problems: &mut Vec<String>) -> Option<String> {
+		let attr = content.get(0)?;
+		let code = content.get(1)?.as_str()?;
+		let id = attr.get(0).and_then(Value::as_str).unwrap_or_default();
+		let classes: Vec<&str> = attr
+			.get(1)
+			.and_then(Value::as_array)
+			.map(|classes| classes.iter().filter_map(Value::as_str).collect())
+			.unwrap_or_default();
+		let attribute = |key: &str| attribute(attr, key);
+
+		let numbered = self.line_numbers
+			|| classes
+				.iter()
+				.any(|class| matches!(*class, "numberLines" | "number-lines"));
+		let first_number: usize = attribute("startFrom")
+			.and_then(|start| start.parse().ok())
+			.unwrap_or(1);
+		let emphasised = match attribute("hl_lines") {
+			Some(spec) => {
+				let (lines, spec_problems) = parse_line_ranges(spec, code.lines().count());
+				problems.extend(spec_problems);
+				lines
+			}
+			None => BTreeSet::new(),
+		};
+		let language = classes
+			.iter()
+			.find(|class| !matches!(**class, "numberLines" | "number-lines"))
+			.copied();
+
+		let syntax = language
+			.and_then(|language| self.syntaxes.find_syntax_by_token(language))
+			.unwrap_or_else(|| self.syntaxes.find_syntax_plain_text());
+		let mut state = ParseState::new(syntax);
+		let mut stack = ScopeStack::new();
+
+		let mut html = format!(
+			"<pre class=\"highlight syn-code{}\"",
+			if numbered { " numbered" } else { "" }
+		);
+		if !id.is_empty() {
+			html.push_str(&format!(" id=\"{}\"", escape_html(id)));
+		}
+		if let Some(title) = attribute("title") {
+			html.push_str(&format!(" data-title=\"{}\"", escape_html(title)));
+		}
+		if let Some(language) = language {
+			html.push_str(&format!(" data-lang=\"{}\"", escape_html(language)));
+		}
+		html.push_str("><code>");
+
+		for (index, line) in code.lines().enumerate() {
+			if index > 0 {
+				html.push('\n');
+			}
+			// Spans are closed at the end of each line and reopened on the next, so every
+			// line is a self-contained element that can be numbered and emphasised.
+			let open: String = stack
+				.as_slice()
+				.iter()
+				.map(|scope| scope_span(*scope))
+				.collect();
+			let depth = stack.len() as isize;
+			let line = format!("{line}\n");
+			let ops = state.parse_line(&line, &self.syntaxes).ok()?;
+			let (mut tokens, delta) =
+				line_tokens_to_classed_spans(&line, &ops, CLASS_STYLE, &mut stack).ok()?;
+			if let Some(newline) = tokens.rfind('\n') {
+				tokens.remove(newline);
+			}
+			let close = "</span>".repeat((depth + delta).max(0) as usize);
+
+			let number = index + 1;
+			let class = if emphasised.contains(&number) {
+				"code-line highlighted"
+			} else {
+				"code-line"
+			};
+			html.push_str(&format!("<span class=\"{class}\""));
+			if numbered {
+				html.push_str(&format!(" data-line=\"{}\"", first_number + index));
+			}
+			html.push_str(&format!(">{open}{tokens}{close}</span>"));
+		}
+
+		html.push_str("</code></pre>");
+		Some(html)
+	}
+}
+
+/// Prefixes every selector in syntect's `css` with `scope`, dropping comments.
+fn scope_css(css: &str, scope: &str) -> String {
+	let mut uncommented = String::new();
+	let mut rest = css;
+	while let Some(start) = rest.find("/*") {
+		uncommented.push_str(&rest[..start]);
+		rest = rest[start..]
+			.find("*/")
+			.map_or("", |end| &rest[start + end + 2..]);
+	}
+	uncommented.push_str(rest);
+
+	let mut scoped = String::new();
+	for rule in uncommented.split_inclusive('}') {
+		let Some((selectors, body)) = rule.split_once('{') else {
+			continue;
+		};
+		let selectors: Vec<String> = selectors
+			.split(',')
+			.map(str::trim)
+			.filter(|selector| !selector.is_empty())
+			.map(|selector| format!("{scope} {selector}"))
+			.collect();
+		if !selectors.is_empty() {
+			scoped.push_str(&format!("{} {{{}\n", selectors.join(", "), body.trim_end()));
+		}
+	}
+	scoped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn lines(spec: &str, line_count: usize) -> (Vec<usize>, usize) {
+		let (lines, problems) = parse_line_ranges(spec, line_count);
+		(lines.into_iter().collect(), problems.len())
+	}
+
+	#[test]
+	fn parses_lines_and_ranges() {
+		assert_eq!(lines("2", 5), (vec![2], 0));
+		assert_eq!(lines("1-3,5", 5), (vec![1, 2, 3, 5], 0));
+		assert_eq!(lines("1-2 4", 5), (vec![1, 2, 4], 0));
+		assert_eq!(lines("", 5), (vec![], 0));
+	}
+
+	#[test]
+	fn clamps_ranges_to_the_block() {
+		assert_eq!(lines("4-1000000000", 5), (vec![4, 5], 1));
+		assert_eq!(lines("7", 5), (vec![], 1));
+		assert_eq!(lines("6-9,2", 5), (vec![2], 1));
+	}
+
+	#[test]
+	fn reports_malformed_and_reversed_parts() {
+		assert_eq!(lines("a,2", 5), (vec![2], 1));
+		assert_eq!(lines("3-", 5), (vec![], 1));
+		assert_eq!(lines("4-2", 5), (vec![], 1));
+		assert_eq!(lines("0-2", 5), (vec![], 1));
+		let (_, problems) = parse_line_ranges("4-2", 5);
+		assert_eq!(problems, ["`hl_lines` range `4-2` is reversed"]);
+	}
+
+	#[test]
+	fn scopes_every_selector_and_drops_comments() {
+		let css = "/* theme */\n.syn-code {\n color: #000;\n}\n.syn-a, .syn-b { color: red; }\n";
+		assert_eq!(
+			scope_css(css, ":root"),
+			":root .syn-code {\n color: #000;\n}\n:root .syn-a, :root .syn-b { color: red; }\n"
+		);
+	}
+
+	#[test]
+	fn scope_css_handles_unterminated_comments() {
+		assert_eq!(scope_css(".a { x: 1; } /* open", "S"), "S .a { x: 1; }\n");
+	}
+}
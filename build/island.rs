@@ -0,0 +1,118 @@
+//! Component islands: fenced divs and bracketed spans that embed a registered Leptos
+//! component in a page.
+//!
+//! ```markdown
+//! ::: {.component name="Button" increment=5}
+//! Fallback shown until the component mounts.
+//! :::
+//!
+//! Inline: []{.component name="Button"}
+//! ```
+//!
+//! The build replaces them with placeholders carrying the component name and its props as
+//! JSON; the app mounts the registered component into each placeholder.
+
+use serde_json::{Map, Value};
+
+use crate::ast::{classes, escape_html, raw_block, raw_inline};
+
+/// Attributes of a `Div` or `Span` with the `component` class, and its content.
+fn island_parts(block: &Value) -> Option<(&'static str, &Value, &Vec<Value>)> {
+	let tag = match block.get("t").and_then(Value::as_str)? {
+		"Div" => "div",
+		"Span" => "span",
+		_ => return None,
+	};
+	let content = block.get("c")?;
+	let attr = content.get(0)?;
+	let is_island = classes(attr).contains(&"component");
+	is_island.then_some((tag, attr, content.get(1)?.as_array()?))
+}
+
+/// Reads an attribute value as a JSON prop: numbers and booleans keep their type, anything
+/// else is a string.
+fn prop_value(value: &str) -> Value {
+	match value {
+		"true" => Value::Bool(true),
+		"false" => Value::Bool(false),
+		_ => serde_json::from_str::<serde_json::Number>(value)
+			.map_or_else(|_| Value::String(value.to_string()), Value::Number),
+	}
+}
+
+/// Replaces component divs and spans with island placeholders, keeping their content as
+/// the fallback. Islands without a `name` are left as they are and reported in `errors`,
+/// each with the index of its island in document order.
+pub fn transform_islands(value: &mut Value, errors: &mut Vec<(usize, String)>) {
+	let mut islands = 0;
+	transform(value, &mut islands, errors);
+}
+
+fn transform(value: &mut Value, islands: &mut usize, errors: &mut Vec<(usize, String)>) {
+	match value {
+		Value::Array(items) => {
+			let mut transformed = Vec::with_capacity(items.len());
+			for mut item in items.drain(..) {
+				// Numbered ahead of the islands nested in it, as they appear in the source.
+				let index = island_parts(&item).map(|_| {
+					*islands += 1;
+					*islands - 1
+				});
+				transform(&mut item, islands, errors);
+				match index.and_then(|index| expand(&item, index, errors)) {
+					Some(blocks) => transformed.extend(blocks),
+					None => transformed.push(item),
+				}
+			}
+			*items = transformed;
+		}
+		Value::Object(map) => {
+			for (_, v) in map.iter_mut() {
+				transform(v, islands, errors);
+			}
+		}
+		_ => {}
+	}
+}
+
+fn expand(block: &Value, index: usize, errors: &mut Vec<(usize, String)>) -> Option<Vec<Value>> {
+	let (tag, attr, content) = island_parts(block)?;
+	let mut name = None;
+	let mut props = Map::new();
+	for pair in attr.get(2)?.as_array()? {
+		let (Some(key), Some(value)) = (pair.get(0)?.as_str(), pair.get(1)?.as_str()) else {
+			continue;
+		};
+		match key {
+			"name" => name = Some(value),
+			_ => {
+				props.insert(key.to_string(), prop_value(value));
+			}
+		}
+	}
+	let Some(name) = name.filter(|name| !name.is_empty()) else {
+		errors.push((
+			index,
+			"component island without a `name` attribute".to_string(),
+		));
+		return None;
+	};
+
+	let id = attr
+		.get(0)
+		.and_then(Value::as_str)
+		.filter(|id| !id.is_empty())
+		.map(|id| format!(" id=\"{}\"", escape_html(id)))
+		.unwrap_or_default();
+	let props = Value::Object(props).to_string();
+	let open = format!(
+		"<{tag} class=\"component-island\"{id} data-component=\"{}\" data-props=\"{}\">",
+		escape_html(name),
+		escape_html(&props),
+	);
+	let raw = if tag == "div" { raw_block } else { raw_inline };
+	let mut blocks = vec![raw(open)];
+	blocks.extend(content.iter().cloned());
+	blocks.push(raw(format!("</{tag}>")));
+	Some(blocks)
+}
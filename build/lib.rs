@@ -0,0 +1,16 @@
+//! Markdown filters, content checks and generated code used by the build script in
+//! `main.rs`. They live in this crate so `cargo test --workspace` runs their unit tests.
+
+pub mod ast;
+pub mod callout;
+pub mod category;
+pub mod config;
+pub mod diagnostics;
+pub mod frontmatter;
+pub mod highlight;
+pub mod island;
+pub mod quiz;
+pub mod search;
+pub mod tabs;
+#[path = "../src/search/tokenize.rs"]
+mod tokenize;
@@ -0,0 +1,488 @@
+//! Build script that converts Markdown to HTML and extracts media for the CSR app.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::{env, fs};
+
+use bezel_build::ast::{add_heading_anchors, collect_headings, rewrite_links};
+use bezel_build::callout::Callouts;
+use bezel_build::category::{
+	CATEGORY_STRUCT, categories_rust, collect_category_files, module_slug,
+};
+use bezel_build::config::{CONFIG_FILE, Config};
+use bezel_build::diagnostics::{Diagnostics, attribute_line};
+use bezel_build::frontmatter::{Frontmatter, extra_value, extract_frontmatter, key_line};
+use bezel_build::highlight::Highlighter;
+use bezel_build::island::transform_islands;
+use bezel_build::quiz::{QUIZ_STRUCT, Quizzes, quizzes_rust, without_quizzes};
+use bezel_build::search::{SearchIndex, collect_sections};
+use bezel_build::tabs::TabGroups;
+use pandoc::{
+	InputFormat, InputKind, MarkdownExtension, OutputFormat, OutputKind, PandocOption, PandocOutput,
+};
+use serde_json::Value;
+use walkdir::WalkDir;
+
+/// Definition of the generated `Page` type, emitted ahead of `PAGES`.
+const PAGE_STRUCT: &str = r#"// Fields mirror frontmatter; not every one is rendered by the app.
+#[allow(dead_code)]
+pub struct Page {
+	pub slug: &'static str,
+	pub title: &'static str,
+	pub category: &'static str,
+	pub order: u32,
+	pub description: Option<&'static str>,
+	pub tags: &'static [&'static str],
+	pub authors: &'static [&'static str],
+	pub draft: bool,
+	pub date: Option<&'static str>,
+	pub updated: Option<&'static str>,
+	pub aliases: &'static [&'static str],
+	/// Canonical slugs of `prev`/`next` frontmatter overrides.
+	pub prev: Option<&'static str>,
+	pub next: Option<&'static str>,
+	/// Free-form frontmatter values; lists and maps are JSON-encoded.
+	pub extra: &'static [(&'static str, &'static str)],
+	/// Headings in document order; nesting follows `level`.
+	pub toc: &'static [TocEntry],
+	/// Quizzes in document order, from `.quiz` divs.
+	pub quizzes: &'static [Quiz],
+	pub html: &'static str,
+}
+
+pub struct TocEntry {
+	pub level: u8,
+	pub id: &'static str,
+	pub text: &'static str,
+}
+"#;
+
+/// Derives a page slug from a Markdown file path.
+/// `index.md` takes its folder's slug; sibling files become sub-pages at `<folder>/<file stem>`.
+fn page_slug(root: &Path, path: &Path) -> String {
+	let module_slug = module_slug(root, path.parent().unwrap_or(Path::new(".")));
+	let stem = path
+		.file_stem()
+		.and_then(|stem| stem.to_str())
+		.expect("utf8 markdown file name");
+	if stem == "index" {
+		module_slug
+	} else if module_slug.is_empty() {
+		stem.to_string()
+	} else {
+		format!("{module_slug}/{stem}")
+	}
+}
+
+/// Resolves `.` and `..` in `path` without touching the file system.
+fn normalize_path(path: &Path) -> PathBuf {
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir if normalized.file_name().is_some() => {
+				normalized.pop();
+			}
+			component => normalized.push(component),
+		}
+	}
+	normalized
+}
+
+/// A Markdown file discovered under a content root, validated before rendering.
+struct Source {
+	path: PathBuf,
+	slug: String,
+	frontmatter: Frontmatter,
+	body: String,
+}
+
+/// Walks the content roots, parsing frontmatter and deriving slugs for every Markdown file.
+/// Duplicate slugs are reported and only the first file claiming a slug is kept; aliases
+/// clashing with other pages are reported once every file is collected.
+fn collect_sources(roots: &[String], strict: bool, diagnostics: &mut Diagnostics) -> Vec<Source> {
+	let mut sources: Vec<Source> = Vec::new();
+
+	let entries = roots.iter().flat_map(|root| {
+		WalkDir::new(root)
+			.sort_by_file_name()
+			.into_iter()
+			.filter_map(Result::ok)
+			.map(move |entry| (Path::new(root), entry))
+	});
+	for (root, entry) in
+		entries.filter(|(_, e)| e.path().extension().and_then(|ext| ext.to_str()) == Some("md"))
+	{
+		let path = entry.path();
+		let slug = page_slug(root, path);
+		let markdown = fs::read_to_string(path).expect("read markdown");
+		let (frontmatter, body) = extract_frontmatter(&markdown, path, strict, diagnostics);
+
+		if let Some(existing) = sources.iter().find(|source| source.slug == slug) {
+			diagnostics.error(
+				path,
+				1,
+				format!(
+					"duplicate slug `{slug}` (already used by {})",
+					existing.path.display()
+				),
+			);
+			continue;
+		}
+
+		sources.push(Source {
+			path: path.to_path_buf(),
+			slug,
+			frontmatter,
+			body: body.to_string(),
+		});
+	}
+
+	check_aliases(&sources, diagnostics);
+	sources
+}
+
+/// Reports aliases that are the slug of another page, or an alias of an earlier one.
+fn check_aliases(sources: &[Source], diagnostics: &mut Diagnostics) {
+	for (index, source) in sources.iter().enumerate() {
+		let mut markdown = None;
+		for alias in &source.frontmatter.aliases {
+			let existing = sources.iter().enumerate().find(|(other_index, other)| {
+				*other_index != index
+					&& (other.slug == *alias
+						|| (*other_index < index && other.frontmatter.aliases.contains(alias)))
+			});
+			if let Some((_, existing)) = existing {
+				let markdown = markdown
+					.get_or_insert_with(|| fs::read_to_string(&source.path).unwrap_or_default());
+				diagnostics.error(
+					&source.path,
+					key_line(markdown, "aliases"),
+					format!(
+						"alias `{alias}` is already used by {}",
+						existing.path.display()
+					),
+				);
+			}
+		}
+	}
+}
+
+/// Finds the source whose slug or alias is `slug`.
+fn find_source<'a>(sources: &'a [Source], slug: &str) -> Option<&'a Source> {
+	sources.iter().find(|source| {
+		source.slug == slug || source.frontmatter.aliases.iter().any(|alias| alias == slug)
+	})
+}
+
+/// Reports `prev`/`next` overrides naming unknown pages, or drafts left out of the build.
+fn check_sequence_links(sources: &[Source], include_drafts: bool, diagnostics: &mut Diagnostics) {
+	for source in sources {
+		let frontmatter = &source.frontmatter;
+		if frontmatter.prev.is_none() && frontmatter.next.is_none() {
+			continue;
+		}
+		let markdown = fs::read_to_string(&source.path).unwrap_or_default();
+		for (key, target) in [("prev", &frontmatter.prev), ("next", &frontmatter.next)] {
+			let Some(target) = target else {
+				continue;
+			};
+			match find_source(sources, target) {
+				None => diagnostics.error(
+					&source.path,
+					key_line(&markdown, key),
+					format!("`{key}` points to unknown page `{target}`"),
+				),
+				Some(linked) if linked.frontmatter.draft && !include_drafts => diagnostics.warning(
+					&source.path,
+					key_line(&markdown, key),
+					format!("`{key}` points to draft `{target}`, which is left out of this build"),
+				),
+				Some(_) => {}
+			}
+		}
+	}
+}
+
+/// Reads an on/off environment variable: `0` and `false` turn it off, any other value turns
+/// it on, and `None` means it is unset.
+fn env_flag(name: &str) -> Option<bool> {
+	env::var(name)
+		.ok()
+		.map(|value| !matches!(value.as_str(), "0" | "false"))
+}
+
+/// Whether pages marked `draft: true` are rendered.
+/// Drafts are kept in debug builds and dropped from release builds; setting
+/// `BEZEL_INCLUDE_DRAFTS` overrides this (`0` or `false` excludes them).
+fn include_drafts() -> bool {
+	env_flag("BEZEL_INCLUDE_DRAFTS")
+		.unwrap_or_else(|| env::var("PROFILE").as_deref() == Ok("debug"))
+}
+
+fn main() {
+	println!("cargo:rerun-if-changed={CONFIG_FILE}");
+	println!("cargo:rerun-if-env-changed=BEZEL_OFFLINE");
+	println!("cargo:rerun-if-env-changed=BEZEL_STRICT");
+	println!("cargo:rerun-if-env-changed=BEZEL_INCLUDE_DRAFTS");
+
+	// Set BEZEL_STRICT=1 to reject unknown frontmatter keys instead of warning about them.
+	let strict = env_flag("BEZEL_STRICT").unwrap_or(false);
+	let include_drafts = include_drafts();
+
+	let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+	let target_dir =
+		PathBuf::from(env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".into()));
+	let generated_assets_root = target_dir.join("generated-assets");
+	let generated = out_dir.join("content.rs");
+	let mut generated_code = format!("{PAGE_STRUCT}{QUIZ_STRUCT}\npub const PAGES: &[Page] = &[\n");
+	let mut search_index = SearchIndex::default();
+	let mut page_count = 0;
+
+	let _ = fs::remove_dir_all(&generated_assets_root);
+	fs::create_dir_all(&generated_assets_root).expect("create generated assets root");
+
+	let mut diagnostics = Diagnostics::default();
+	let config = Config::load(&mut diagnostics);
+	for root in &config.content.roots {
+		println!("cargo:rerun-if-changed={root}");
+	}
+	let sources = collect_sources(&config.content.roots, strict, &mut diagnostics);
+	let folder_categories = collect_category_files(&config.content.roots, &mut diagnostics);
+	check_sequence_links(&sources, include_drafts, &mut diagnostics);
+	diagnostics.finish();
+	let highlighter = Rc::new(Highlighter::new(&config.markdown.highlight));
+	let callouts = Rc::new(Callouts::new(&config.callouts));
+	// Problems only found while rendering, such as links to unknown Markdown files.
+	let mut render_diagnostics = Diagnostics::default();
+	// Routes of rendered pages by Markdown file, for links between pages.
+	let page_routes: Rc<HashMap<PathBuf, String>> = Rc::new(
+		sources
+			.iter()
+			.filter(|source| include_drafts || !source.frontmatter.draft)
+			.map(|source| {
+				(
+					normalize_path(&source.path),
+					format!("/docs/{}", source.slug),
+				)
+			})
+			.collect(),
+	);
+
+	let extensions: Vec<MarkdownExtension> = config
+		.markdown
+		.extensions
+		.iter()
+		.cloned()
+		.map(MarkdownExtension::Other)
+		.collect();
+	// Use relative paths for offline builds (file:// protocol), the configured base otherwise.
+	// Set BEZEL_OFFLINE=1 environment variable for offline builds
+	let assets_prefix = if env::var("BEZEL_OFFLINE").is_ok() {
+		"./assets/".to_string()
+	} else {
+		let base_url = config.assets.base_url.as_deref().unwrap_or("/assets/");
+		format!("{}/", base_url.trim_end_matches('/'))
+	};
+
+	for source in &sources {
+		if source.frontmatter.draft && !include_drafts {
+			continue;
+		}
+		let path = source.path.as_path();
+		let module_dir = path.parent().unwrap_or(Path::new("."));
+		let slug = &source.slug;
+		// Extract media into a single pooled generated assets directory.
+		// Pandoc will hash filenames based on file content to deduplicate and avoid collisions.
+		let media_dir = generated_assets_root.clone();
+
+		let frontmatter = &source.frontmatter;
+		let title = frontmatter
+			.title
+			.clone()
+			.unwrap_or_else(|| slug.rsplit('/').next().unwrap_or_default().to_string());
+		// Sub-pages default to the category of their module's index page.
+		let category = frontmatter
+			.category
+			.clone()
+			.or_else(|| {
+				let index_path = module_dir.join("index.md");
+				sources
+					.iter()
+					.find(|source| source.path == index_path)
+					.and_then(|index| index.frontmatter.category.clone())
+			})
+			.unwrap_or_else(|| config.content.default_category.clone());
+		let order = frontmatter.order.unwrap_or(config.content.default_order);
+		// Overrides are stored by canonical slug; excluded drafts fall back to navigation order.
+		let [prev, next] = [&frontmatter.prev, &frontmatter.next].map(|target| {
+			target
+				.as_deref()
+				.and_then(|target| find_source(&sources, target))
+				.filter(|linked| include_drafts || !linked.frontmatter.draft)
+				.map(|linked| linked.slug.as_str())
+		});
+
+		fs::create_dir_all(&media_dir).expect("create media dir");
+
+		let mut pandoc = pandoc::new();
+		pandoc.set_input(InputKind::Pipe(source.body.clone()));
+		pandoc.add_option(PandocOption::ResourcePath(vec![module_dir.into()]));
+		pandoc.set_input_format(InputFormat::Markdown, extensions.clone());
+		pandoc.set_output_format(OutputFormat::Html, Vec::new());
+		pandoc.set_output(OutputKind::Pipe);
+		if let Some(math) = config.markdown.math.pandoc_option() {
+			pandoc.add_option(math);
+		}
+		pandoc.add_option(PandocOption::ExtractMedia(media_dir.clone()));
+		// The filter runs inside `execute`; headings and search sections are handed back
+		// through these cells.
+		let toc = Rc::new(RefCell::new(Vec::new()));
+		let sections = Rc::new(RefCell::new(Vec::new()));
+		let filter_toc = Rc::clone(&toc);
+		let filter_sections = Rc::clone(&sections);
+		let filter_title = title.clone();
+		let filter_highlighter = Rc::clone(&highlighter);
+		let filter_callouts = Rc::clone(&callouts);
+		let broken_links = Rc::new(RefCell::new(Vec::new()));
+		let filter_broken_links = Rc::clone(&broken_links);
+		let highlight_problems = Rc::new(RefCell::new(Vec::new()));
+		let filter_highlight_problems = Rc::clone(&highlight_problems);
+		let island_errors = Rc::new(RefCell::new(Vec::new()));
+		let filter_island_errors = Rc::clone(&island_errors);
+		let quizzes = Rc::new(RefCell::new(Quizzes::new(slug)));
+		let filter_quizzes = Rc::clone(&quizzes);
+		let filter_routes = Rc::clone(&page_routes);
+		let filter_module_dir = module_dir.to_path_buf();
+		pandoc.add_filter(move |json| {
+			let mut doc: Value = serde_json::from_str(&json).expect("parse pandoc json");
+			rewrite_links(&mut doc, &mut |target| {
+				let path = normalize_path(&filter_module_dir.join(target));
+				let route = filter_routes.get(&path).cloned();
+				if route.is_none() {
+					filter_broken_links.borrow_mut().push(target.to_string());
+				}
+				route
+			});
+			// Quizzes are left out of the table of contents and the search index.
+			if let Some(blocks) = doc.get("blocks").map(without_quizzes) {
+				collect_headings(&blocks, &mut filter_toc.borrow_mut());
+				*filter_sections.borrow_mut() = collect_sections(&blocks, &filter_title);
+			}
+			// After collecting headings and sections, which index the plain text.
+			add_heading_anchors(&mut doc);
+			transform_islands(&mut doc, &mut filter_island_errors.borrow_mut());
+			TabGroups::default().transform(&mut doc);
+			filter_callouts.transform(&mut doc);
+			filter_highlighter
+				.highlight_code_blocks(&mut doc, &mut filter_highlight_problems.borrow_mut());
+			// Last, so quiz prompts and explanations carry highlighted code.
+			filter_quizzes.borrow_mut().transform(&mut doc);
+			serde_json::to_string(&doc).expect("serialize pandoc json")
+		});
+
+		let html = match pandoc.execute().expect("pandoc") {
+			PandocOutput::ToBuffer(html) => {
+				let media_prefix = media_dir.to_string_lossy().replace('\\', "/") + "/";
+				html.replace(&media_prefix, &assets_prefix)
+			}
+			PandocOutput::ToBufferRaw(bytes) => String::from_utf8(bytes).expect("utf8 html"),
+			PandocOutput::ToFile(path) => fs::read_to_string(path).expect("read html"),
+		};
+
+		if !broken_links.borrow().is_empty() {
+			let markdown = fs::read_to_string(path).expect("read markdown");
+			for target in broken_links.take() {
+				let line = markdown
+					.lines()
+					.position(|line| line.contains(&target))
+					.map_or(1, |index| index + 1);
+				render_diagnostics.warning(
+					path,
+					line,
+					format!("link to `{target}` does not point to a page in this build"),
+				);
+			}
+		}
+
+		if !highlight_problems.borrow().is_empty() {
+			let markdown = fs::read_to_string(path).expect("read markdown");
+			for (block, message) in highlight_problems.take() {
+				let line = attribute_line(&markdown, block, |token| token.starts_with("hl_lines="));
+				render_diagnostics.warning(path, line, message);
+			}
+		}
+
+		if !island_errors.borrow().is_empty() {
+			let markdown = fs::read_to_string(path).expect("read markdown");
+			for (island, message) in island_errors.take() {
+				let line = attribute_line(&markdown, island, |token| token == ".component");
+				render_diagnostics.warning(path, line, message);
+			}
+		}
+
+		let quizzes = quizzes.borrow();
+		if !quizzes.errors.is_empty() {
+			let markdown = fs::read_to_string(path).expect("read markdown");
+			for (quiz, message) in &quizzes.errors {
+				let line = attribute_line(&markdown, *quiz, |token| token == ".quiz");
+				render_diagnostics.error(path, line, message.as_str());
+			}
+		}
+		let quizzes = quizzes_rust(&quizzes.quizzes);
+
+		let html_path = out_dir.join("pages").join(slug).join("index.html");
+		if let Some(parent) = html_path.parent() {
+			fs::create_dir_all(parent).expect("create module dir");
+		}
+		fs::write(&html_path, html).expect("write html");
+
+		let include_path = html_path.to_str().expect("html path").replace('\\', "/");
+		let Frontmatter {
+			description,
+			tags,
+			authors,
+			draft,
+			date,
+			updated,
+			aliases,
+			extra,
+			..
+		} = frontmatter;
+		let toc = toc
+			.borrow()
+			.iter()
+			.map(|entry| {
+				format!(
+					"TocEntry {{ level: {}, id: {:?}, text: {:?} }}",
+					entry.level, entry.id, entry.text
+				)
+			})
+			.collect::<Vec<_>>()
+			.join(", ");
+		let extra: Vec<(&String, String)> = extra
+			.iter()
+			.map(|(key, value)| (key, extra_value(value)))
+			.collect();
+		generated_code.push_str(&format!(
+			"    Page {{ slug: {slug:?}, title: {title:?}, category: {category:?}, order: {order}, description: {description:?}, tags: &{tags:?}, authors: &{authors:?}, draft: {draft}, date: {date:?}, updated: {updated:?}, aliases: &{aliases:?}, prev: {prev:?}, next: {next:?}, extra: &{extra:?}, toc: &[{toc}], quizzes: {quizzes}, html: include_str!(r\"{include_path}\") }},\n"
+		));
+		search_index.add_page(page_count, sections.take());
+		page_count += 1;
+	}
+
+	generated_code.push_str("];\n");
+	generated_code.push_str(CATEGORY_STRUCT);
+	generated_code.push_str(&categories_rust(
+		"FOLDER_CATEGORIES",
+		folder_categories.iter().map(|(slug, meta)| (slug, meta)),
+	));
+	generated_code.push_str(&categories_rust("CATEGORIES", &config.categories));
+	fs::write(generated, generated_code).expect("write generated rust");
+	fs::write(out_dir.join("search_index.rs"), search_index.to_rust()).expect("write search index");
+	fs::write(out_dir.join("site.rs"), config.site_rust()).expect("write site config");
+	fs::write(out_dir.join("highlight.css"), highlighter.css()).expect("write highlight css");
+	render_diagnostics.finish();
+}
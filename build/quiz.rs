@@ -0,0 +1,662 @@
+//! Quizzes from fenced divs: a `.quiz` div of `.question` divs, compiled into data on the
+//! page and replaced by a `Quiz` component island.
+//!
+//! ```markdown
+//! :::: {.quiz #borrowing-check pass=75}
+//! ::: {.question type="single"}
+//! Which statement moves `s`?
+//!
+//! - [ ] `let r = &s;`
+//! - [x] `let t = s;`
+//!
+//! ::: explanation
+//! Assigning a `String` moves it; taking a reference borrows it.
+//! :::
+//! :::
+//!
+//! ::: {.question type="true-false" answer="false"}
+//! A value can have two mutable references at once.
+//! :::
+//!
+//! ::: {.question type="short" accept="borrow*|reference"}
+//! What does `&s` create?
+//! :::
+//! ::::
+//! ```
+//!
+//! Question types are `single` and `multiple` (choices from a task list, `[x]` marking the
+//! correct ones), `true-false` (with `answer`) and `short` (with `accept`: `|`-separated
+//! patterns where `*` matches any text, compared case-insensitively). An `.explanation` div
+//! is shown once the quiz is checked. `pass` is the percentage of correct answers needed to
+//! pass, 100 by default. Other blocks in the quiz, such as an introduction, are kept
+//! above it as they are; question text can hold formatting, lists and code, but not images, tables,
+//! figures or footnotes.
+
+use serde_json::{Value, json};
+
+use crate::ast::{
+	attribute, blocks_html, div_parts, escape_html, inline_html, raw_block, unrendered_elements,
+};
+
+/// Definition of the generated quiz types, emitted ahead of `PAGES`.
+pub const QUIZ_STRUCT: &str = r#"
+pub struct Quiz {
+	pub id: &'static str,
+	/// Percentage of correct answers needed to pass.
+	pub pass: u32,
+	pub questions: &'static [Question],
+}
+
+pub struct Question {
+	/// Prompt as HTML.
+	pub prompt: &'static str,
+	pub answer: Answer,
+	/// Shown with the results, as HTML.
+	pub explanation: Option<&'static str>,
+}
+
+// Sites without quizzes of every type never construct some variants.
+#[allow(dead_code)]
+pub enum Answer {
+	Single(&'static [Choice]),
+	Multiple(&'static [Choice]),
+	TrueFalse(bool),
+	/// Accepted patterns; `*` matches any text.
+	Short(&'static [&'static str]),
+}
+
+pub struct Choice {
+	/// Label as HTML.
+	pub html: &'static str,
+	pub correct: bool,
+}
+"#;
+
+/// Task list markers Pandoc puts before the text of checked and unchecked items.
+const CHECKED: &str = "☒";
+const UNCHECKED: &str = "☐";
+
+pub enum Answer {
+	Single(Vec<(String, bool)>),
+	Multiple(Vec<(String, bool)>),
+	TrueFalse(bool),
+	Short(Vec<String>),
+}
+
+pub struct Question {
+	prompt: String,
+	answer: Answer,
+	explanation: Option<String>,
+}
+
+pub struct Quiz {
+	id: String,
+	pass: u32,
+	questions: Vec<Question>,
+}
+
+impl Quiz {
+	fn to_rust(&self) -> String {
+		let choices = |choices: &[(String, bool)]| {
+			let choices: Vec<String> = choices
+				.iter()
+				.map(|(html, correct)| format!("Choice {{ html: {html:?}, correct: {correct} }}"))
+				.collect();
+			format!("&[{}]", choices.join(", "))
+		};
+		let questions: Vec<String> = self
+			.questions
+			.iter()
+			.map(|question| {
+				let answer = match &question.answer {
+					Answer::Single(list) => format!("Answer::Single({})", choices(list)),
+					Answer::Multiple(list) => format!("Answer::Multiple({})", choices(list)),
+					Answer::TrueFalse(answer) => format!("Answer::TrueFalse({answer})"),
+					Answer::Short(patterns) => format!("Answer::Short(&{patterns:?})"),
+				};
+				format!(
+					"Question {{ prompt: {:?}, answer: {answer}, explanation: {:?} }}",
+					question.prompt, question.explanation
+				)
+			})
+			.collect();
+		format!(
+			"Quiz {{ id: {:?}, pass: {}, questions: &[{}] }}",
+			self.id,
+			self.pass,
+			questions.join(", ")
+		)
+	}
+}
+
+/// Renders a page's quizzes as the value of its `quizzes` field.
+pub fn quizzes_rust(quizzes: &[Quiz]) -> String {
+	let quizzes: Vec<String> = quizzes.iter().map(Quiz::to_rust).collect();
+	format!("&[{}]", quizzes.join(", "))
+}
+
+/// Reads a task list item into its label HTML and whether it is checked.
+fn task_item(item: &Value) -> Option<(String, bool)> {
+	let first = item.get(0)?;
+	if !matches!(first.get("t")?.as_str()?, "Plain" | "Para") {
+		return None;
+	}
+	let inlines = first.get("c")?.as_array()?;
+	let marker = inlines.first()?;
+	let checked = match marker.get("c")?.as_str()? {
+		CHECKED => true,
+		UNCHECKED => false,
+		_ => return None,
+	};
+	let label = Value::Array(inlines.iter().skip(2).cloned().collect());
+	let mut html = inline_html(&label);
+	if let Some(rest) = item.as_array().filter(|blocks| blocks.len() > 1) {
+		html.push_str(&blocks_html(&Value::Array(rest[1..].to_vec())));
+	}
+	Some((html, checked))
+}
+
+/// Choices of a task list block, or `None` if the block is not a task list.
+fn task_list(block: &Value) -> Option<Vec<(String, bool)>> {
+	if block.get("t")?.as_str()? != "BulletList" {
+		return None;
+	}
+	block.get("c")?.as_array()?.iter().map(task_item).collect()
+}
+
+/// Whether `block` is a `.quiz` div.
+fn is_quiz(block: &Value) -> bool {
+	div_parts(block).is_some_and(|(_, classes, ..)| classes.contains(&"quiz"))
+}
+
+/// Copy of `value` without its `.quiz` divs, whose headings, answers and explanations are
+/// not part of the page's outline or search text.
+pub fn without_quizzes(value: &Value) -> Value {
+	match value {
+		Value::Array(items) => Value::Array(
+			items
+				.iter()
+				.filter(|item| !is_quiz(item))
+				.map(without_quizzes)
+				.collect(),
+		),
+		Value::Object(map) => Value::Object(
+			map.iter()
+				.map(|(key, v)| (key.clone(), without_quizzes(v)))
+				.collect(),
+		),
+		value => value.clone(),
+	}
+}
+
+/// Compiles quiz divs into [`Quiz`] data, replacing each with a placeholder for the `Quiz`
+/// component. Problems are collected in `errors`, prefixed with the quiz id and paired with
+/// the index of the quiz in document order.
+pub struct Quizzes {
+	slug: String,
+	pub quizzes: Vec<Quiz>,
+	pub errors: Vec<(usize, String)>,
+}
+
+impl Quizzes {
+	pub fn new(slug: &str) -> Self {
+		Quizzes {
+			slug: slug.to_string(),
+			quizzes: Vec::new(),
+			errors: Vec::new(),
+		}
+	}
+
+	pub fn transform(&mut self, value: &mut Value) {
+		match value {
+			Value::Array(items) => {
+				let mut transformed = Vec::with_capacity(items.len());
+				for mut item in items.drain(..) {
+					match self.compile(&item) {
+						Some(blocks) => transformed.extend(blocks),
+						None => {
+							self.transform(&mut item);
+							transformed.push(item);
+						}
+					}
+				}
+				*items = transformed;
+			}
+			Value::Object(map) => {
+				for (_, v) in map.iter_mut() {
+					self.transform(v);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Compiles a `.quiz` div and returns its intro blocks followed by its placeholder;
+	/// other blocks yield `None`. The intro stays outside the placeholder, whose content
+	/// the app replaces when it mounts the component.
+	fn compile(&mut self, block: &Value) -> Option<Vec<Value>> {
+		if !is_quiz(block) {
+			return None;
+		}
+		let (id, _, attr, blocks) = div_parts(block)?;
+		let index = self.quizzes.len();
+		let id = if id.is_empty() {
+			format!("quiz-{}", index + 1)
+		} else {
+			id.to_string()
+		};
+		let mut error =
+			|message: String| self.errors.push((index, format!("quiz `{id}`: {message}")));
+		if self.quizzes.iter().any(|quiz| quiz.id == id) {
+			error("duplicate quiz id on this page".to_string());
+		}
+		let pass = match attribute(attr, "pass") {
+			None => 100,
+			Some(pass) => match pass.parse::<u32>() {
+				Ok(pass @ 1..=100) => pass,
+				_ => {
+					error(format!(
+						"`pass` must be a percentage from 1 to 100, found `{pass}`"
+					));
+					100
+				}
+			},
+		};
+		let mut intro = Vec::new();
+		let mut question_divs = Vec::new();
+		let mut questions = Vec::new();
+		let mut number = 0;
+		for block in blocks {
+			match div_parts(block).filter(|(_, classes, ..)| classes.contains(&"question")) {
+				Some((_, _, attr, blocks)) => {
+					question_divs.push(block.clone());
+					number += 1;
+					match question(attr, blocks) {
+						Ok(question) => questions.push(question),
+						Err(message) => error(format!("question {number}: {message}")),
+					}
+				}
+				None => intro.push(block.clone()),
+			}
+		}
+		if number == 0 {
+			error("has no `.question` divs".to_string());
+		}
+		// Questions are rendered by `blocks_html`, which cannot show these; the intro is
+		// left to Pandoc.
+		for element in unrendered_elements(&Value::Array(question_divs)) {
+			error(format!("contains {element}, which quizzes cannot show"));
+		}
+
+		let props = json!({ "page": self.slug, "quiz": id }).to_string();
+		let html = format!(
+			"<div class=\"component-island quiz-island\" id=\"{}\" data-component=\"Quiz\" data-props=\"{}\"><p class=\"quiz-fallback\">Quiz: {} questions</p></div>",
+			escape_html(&id),
+			escape_html(&props),
+			questions.len(),
+		);
+		self.quizzes.push(Quiz {
+			id,
+			pass,
+			questions,
+		});
+		intro.push(raw_block(html));
+		Some(intro)
+	}
+}
+
+/// Compiles a `.question` div, checking that its answer fits its type.
+fn question(attr: &Value, blocks: &[Value]) -> Result<Question, String> {
+	let mut prompt = Vec::new();
+	let mut choices = None;
+	let mut explanation = None;
+	for block in blocks {
+		if let Some(list) = task_list(block) {
+			if choices.is_some() {
+				return Err("has more than one list of choices".to_string());
+			}
+			choices = Some(list);
+		} else if let Some((.., blocks)) =
+			div_parts(block).filter(|(_, classes, ..)| classes.contains(&"explanation"))
+		{
+			explanation = Some(blocks_html(&Value::Array(blocks.clone())));
+		} else {
+			prompt.push(block.clone());
+		}
+	}
+	if prompt.is_empty() {
+		return Err("has no prompt".to_string());
+	}
+
+	let kind = attribute(attr, "type").ok_or("has no `type`")?;
+	if choices.is_some() && matches!(kind, "true-false" | "short") {
+		return Err(format!("`{kind}` questions do not take a list of choices"));
+	}
+	let answer = match kind {
+		"single" | "multiple" => {
+			let choices = choices.ok_or("needs a task list of choices")?;
+			let correct = choices.iter().filter(|(_, correct)| *correct).count();
+			if choices.len() < 2 {
+				return Err("needs at least two choices".to_string());
+			}
+			match kind {
+				"single" if correct != 1 => {
+					return Err(format!("needs exactly one `[x]` choice, found {correct}"));
+				}
+				"single" => Answer::Single(choices),
+				_ if correct == 0 => return Err("needs at least one `[x]` choice".to_string()),
+				_ => Answer::Multiple(choices),
+			}
+		}
+		"true-false" => match attribute(attr, "answer") {
+			Some("true") => Answer::TrueFalse(true),
+			Some("false") => Answer::TrueFalse(false),
+			_ => return Err("needs `answer=\"true\"` or `answer=\"false\"`".to_string()),
+		},
+		"short" => {
+			let patterns: Vec<String> = attribute(attr, "accept")
+				.unwrap_or_default()
+				.split('|')
+				.map(str::trim)
+				.filter(|pattern| !pattern.is_empty())
+				.map(str::to_string)
+				.collect();
+			if patterns.is_empty() {
+				return Err("needs `accept` with at least one pattern".to_string());
+			}
+			Answer::Short(patterns)
+		}
+		other => {
+			return Err(format!(
+				"unknown type `{other}`; expected single, multiple, true-false or short"
+			));
+		}
+	};
+
+	Ok(Question {
+		prompt: blocks_html(&Value::Array(prompt)),
+		answer,
+		explanation,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn inlines(text: &str) -> Vec<Value> {
+		let mut inlines = Vec::new();
+		for (index, word) in text.split(' ').enumerate() {
+			if index > 0 {
+				inlines.push(json!({ "t": "Space" }));
+			}
+			inlines.push(json!({ "t": "Str", "c": word }));
+		}
+		inlines
+	}
+
+	fn para(text: &str) -> Value {
+		json!({ "t": "Para", "c": inlines(text) })
+	}
+
+	fn div(id: &str, classes: &[&str], attrs: &[(&str, &str)], blocks: Vec<Value>) -> Value {
+		json!({ "t": "Div", "c": [[id, classes, attrs], blocks] })
+	}
+
+	fn choices(items: &[(bool, &str)]) -> Value {
+		let items: Vec<Value> = items
+			.iter()
+			.map(|(checked, label)| {
+				let marker = if *checked { CHECKED } else { UNCHECKED };
+				let mut content = vec![json!({ "t": "Str", "c": marker }), json!({ "t": "Space" })];
+				content.extend(inlines(label));
+				json!([{ "t": "Plain", "c": content }])
+			})
+			.collect();
+		json!({ "t": "BulletList", "c": items })
+	}
+
+	fn question(attrs: &[(&str, &str)], blocks: Vec<Value>) -> Value {
+		div("", &["question"], attrs, blocks)
+	}
+
+	fn quiz(id: &str, attrs: &[(&str, &str)], questions: Vec<Value>) -> Value {
+		div(id, &["quiz"], attrs, questions)
+	}
+
+	fn compile(blocks: Vec<Value>) -> (Quizzes, Value) {
+		let mut quizzes = Quizzes::new("rust/borrowing");
+		let mut doc = json!({ "blocks": blocks });
+		quizzes.transform(&mut doc);
+		(quizzes, doc)
+	}
+
+	fn errors(blocks: Vec<Value>) -> Vec<(usize, String)> {
+		compile(blocks).0.errors
+	}
+
+	#[test]
+	fn compiles_every_question_type() {
+		let (quizzes, doc) = compile(vec![quiz(
+			"check",
+			&[("pass", "50")],
+			vec![
+				para("Test yourself."),
+				question(
+					&[("type", "single")],
+					vec![
+						para("Which moves?"),
+						choices(&[(false, "a borrow"), (true, "an assignment")]),
+						div("", &["explanation"], &[], vec![para("Assignment moves.")]),
+					],
+				),
+				question(
+					&[("type", "multiple")],
+					vec![
+						para("Which borrow?"),
+						choices(&[(true, "&s"), (true, "&mut s")]),
+					],
+				),
+				question(
+					&[("type", "true-false"), ("answer", "false")],
+					vec![para("Two mutable borrows are fine.")],
+				),
+				question(
+					&[("type", "short"), ("accept", "borrow* | reference")],
+					vec![para("What does &s create?")],
+				),
+			],
+		)]);
+		assert!(quizzes.errors.is_empty());
+		let [quiz] = &quizzes.quizzes[..] else {
+			panic!("expected one quiz");
+		};
+		assert_eq!((quiz.id.as_str(), quiz.pass), ("check", 50));
+		let answers: Vec<&Answer> = quiz.questions.iter().map(|q| &q.answer).collect();
+		assert!(matches!(answers[0], Answer::Single(choices) if choices[1].1));
+		assert!(matches!(answers[1], Answer::Multiple(choices) if choices.len() == 2));
+		assert!(matches!(answers[2], Answer::TrueFalse(false)));
+		assert!(
+			matches!(answers[3], Answer::Short(patterns) if patterns == &["borrow*", "reference"])
+		);
+		assert_eq!(quiz.questions[0].prompt, "<p>Which moves?</p>");
+		assert_eq!(
+			quiz.questions[0].explanation.as_deref(),
+			Some("<p>Assignment moves.</p>")
+		);
+
+		let placeholder = doc["blocks"][1]["c"][1].as_str().unwrap();
+		assert!(placeholder.contains("data-component=\"Quiz\""));
+	}
+
+	#[test]
+	fn keeps_the_intro_outside_the_placeholder() {
+		let image =
+			json!({ "t": "Para", "c": [{ "t": "Image", "c": [["", [], []], [], ["a.png", ""]] }] });
+		let (quizzes, doc) = compile(vec![quiz(
+			"q",
+			&[],
+			vec![
+				para("Test yourself."),
+				image.clone(),
+				question(
+					&[("type", "true-false"), ("answer", "true")],
+					vec![para("?")],
+				),
+			],
+		)]);
+		assert!(quizzes.errors.is_empty());
+		// Mounting the component replaces the placeholder's content, so the intro must
+		// come before it as ordinary blocks.
+		let blocks = doc["blocks"].as_array().unwrap();
+		assert_eq!(blocks[..2], [para("Test yourself."), image]);
+		let [placeholder] = &blocks[2..] else {
+			panic!("expected the placeholder after the intro");
+		};
+		assert_eq!(placeholder["t"], "RawBlock");
+		let html = placeholder["c"][1].as_str().unwrap();
+		assert!(html.ends_with("<p class=\"quiz-fallback\">Quiz: 1 questions</p></div>"));
+		assert!(!html.contains("Test yourself."));
+	}
+
+	#[test]
+	fn defaults_id_and_pass_mark() {
+		let (quizzes, _) = compile(vec![quiz(
+			"",
+			&[],
+			vec![question(
+				&[("type", "true-false"), ("answer", "true")],
+				vec![para("Yes?")],
+			)],
+		)]);
+		assert_eq!(quizzes.quizzes[0].id, "quiz-1");
+		assert_eq!(quizzes.quizzes[0].pass, 100);
+	}
+
+	#[test]
+	fn rejects_answers_that_do_not_fit_the_type() {
+		let cases = [
+			(
+				question(
+					&[("type", "single")],
+					vec![para("?"), choices(&[(true, "a"), (true, "b")])],
+				),
+				"question 1: needs exactly one `[x]` choice, found 2",
+			),
+			(
+				question(
+					&[("type", "multiple")],
+					vec![para("?"), choices(&[(false, "a"), (false, "b")])],
+				),
+				"question 1: needs at least one `[x]` choice",
+			),
+			(
+				question(
+					&[("type", "single")],
+					vec![para("?"), choices(&[(true, "a")])],
+				),
+				"question 1: needs at least two choices",
+			),
+			(
+				question(
+					&[("type", "true-false"), ("answer", "yes")],
+					vec![para("?")],
+				),
+				"question 1: needs `answer=\"true\"` or `answer=\"false\"`",
+			),
+			(
+				question(
+					&[("type", "short"), ("accept", "x")],
+					vec![para("?"), choices(&[(true, "a"), (false, "b")])],
+				),
+				"question 1: `short` questions do not take a list of choices",
+			),
+			(
+				question(&[("type", "short"), ("accept", " | ")], vec![para("?")]),
+				"question 1: needs `accept` with at least one pattern",
+			),
+			(question(&[], vec![para("?")]), "question 1: has no `type`"),
+			(
+				question(&[("type", "essay")], vec![para("?")]),
+				"question 1: unknown type `essay`; expected single, multiple, true-false or short",
+			),
+			(
+				question(&[("type", "true-false"), ("answer", "true")], vec![]),
+				"question 1: has no prompt",
+			),
+		];
+		for (question, message) in cases {
+			assert_eq!(
+				errors(vec![quiz("q", &[], vec![question])]),
+				[(0, format!("quiz `q`: {message}"))]
+			);
+		}
+	}
+
+	#[test]
+	fn rejects_malformed_quizzes() {
+		let valid = || {
+			question(
+				&[("type", "true-false"), ("answer", "true")],
+				vec![para("?")],
+			)
+		};
+		assert_eq!(
+			errors(vec![quiz("q", &[], vec![para("No questions.")])]),
+			[(0, "quiz `q`: has no `.question` divs".to_string())]
+		);
+		assert_eq!(
+			errors(vec![quiz("q", &[("pass", "0")], vec![valid()])]),
+			[(
+				0,
+				"quiz `q`: `pass` must be a percentage from 1 to 100, found `0`".to_string()
+			)]
+		);
+		let image =
+			json!({ "t": "Para", "c": [{ "t": "Image", "c": [["", [], []], [], ["a.png", ""]] }] });
+		let with_image = question(
+			&[("type", "true-false"), ("answer", "true")],
+			vec![para("?"), image],
+		);
+		assert_eq!(
+			errors(vec![quiz("q", &[], vec![with_image])]),
+			[(
+				0,
+				"quiz `q`: contains an image, which quizzes cannot show".to_string()
+			)]
+		);
+	}
+
+	#[test]
+	fn errors_carry_the_index_of_their_quiz() {
+		let valid = || {
+			question(
+				&[("type", "true-false"), ("answer", "true")],
+				vec![para("?")],
+			)
+		};
+		assert_eq!(
+			errors(vec![
+				quiz("first", &[], vec![valid()]),
+				quiz("first", &[], vec![valid()]),
+			]),
+			[(
+				1,
+				"quiz `first`: duplicate quiz id on this page".to_string()
+			)]
+		);
+	}
+
+	#[test]
+	fn strips_quizzes_for_the_outline() {
+		let blocks = json!([
+			para("Intro"),
+			quiz("q", &[], vec![para("Hidden")]),
+			div("", &["note"], &[], vec![quiz("", &[], vec![])]),
+		]);
+		assert_eq!(
+			without_quizzes(&blocks),
+			json!([para("Intro"), div("", &["note"], &[], vec![])])
+		);
+	}
+}
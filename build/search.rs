@@ -0,0 +1,153 @@
+//! Builds the inverted search index emitted alongside `PAGES`.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::ast::inline_text;
+use crate::tokenize::{normalize, stem, words};
+
+/// Weight of a term occurrence in a section heading or page title.
+const HEADING_WEIGHT: u32 = 5;
+
+/// A heading-delimited part of a page's content.
+pub struct Section {
+	/// Heading id, or empty for the content above the first heading.
+	pub anchor: String,
+	pub heading: String,
+	pub text: String,
+}
+
+/// Appends the plain text of any Pandoc element, separating blocks with spaces.
+fn plain_text(value: &Value, text: &mut String) {
+	match value {
+		Value::Array(items) => {
+			for item in items {
+				plain_text(item, text);
+			}
+		}
+		Value::Object(map) => {
+			let content = map.get("c");
+			match map.get("t").and_then(Value::as_str) {
+				Some("Str") => text.push_str(content.and_then(Value::as_str).unwrap_or_default()),
+				Some("Space" | "SoftBreak" | "LineBreak") => text.push(' '),
+				Some("Code" | "Math" | "CodeBlock") => {
+					let code = content.and_then(|c| c.get(1)).and_then(Value::as_str);
+					text.push_str(code.unwrap_or_default());
+					text.push(' ');
+				}
+				Some("RawInline" | "RawBlock") => {}
+				_ => {
+					if let Some(content) = content {
+						plain_text(content, text);
+					}
+					text.push(' ');
+				}
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Splits a Pandoc document's blocks into sections at each heading.
+/// The first section holds the content above the first heading and is titled `title`.
+pub fn collect_sections(blocks: &Value, title: &str) -> Vec<Section> {
+	let mut sections = vec![Section {
+		anchor: String::new(),
+		heading: title.to_string(),
+		text: String::new(),
+	}];
+	split_sections(blocks, &mut sections);
+	for section in &mut sections {
+		section.text = section
+			.text
+			.split_whitespace()
+			.collect::<Vec<_>>()
+			.join(" ");
+	}
+	sections
+}
+
+fn split_sections(blocks: &Value, sections: &mut Vec<Section>) {
+	for block in blocks.as_array().into_iter().flatten() {
+		let content = block.get("c");
+		match block.get("t").and_then(Value::as_str) {
+			Some("Header") => {
+				let mut heading = String::new();
+				if let Some(inlines) = content.and_then(|c| c.get(2)) {
+					inline_text(inlines, &mut heading);
+				}
+				let anchor = content
+					.and_then(|c| c.get(1))
+					.and_then(|attr| attr.get(0))
+					.and_then(Value::as_str)
+					.unwrap_or_default();
+				sections.push(Section {
+					anchor: anchor.to_string(),
+					heading,
+					text: String::new(),
+				});
+			}
+			// Fenced divs may contain headings of their own.
+			Some("Div") => {
+				if let Some(blocks) = content.and_then(|c| c.get(1)) {
+					split_sections(blocks, sections);
+				}
+			}
+			_ => {
+				let section = sections.last_mut().expect("sections start non-empty");
+				plain_text(block, &mut section.text);
+			}
+		}
+	}
+}
+
+/// Inverted index from stemmed terms to weighted section postings.
+#[derive(Default)]
+pub struct SearchIndex {
+	/// Sections paired with the index of their page in `PAGES`.
+	sections: Vec<(usize, Section)>,
+	terms: BTreeMap<String, BTreeMap<usize, u32>>,
+}
+
+impl SearchIndex {
+	/// Adds the sections of the page at `page` in `PAGES`.
+	pub fn add_page(&mut self, page: usize, sections: Vec<Section>) {
+		for section in sections {
+			let id = self.sections.len();
+			for (text, weight) in [(&section.heading, HEADING_WEIGHT), (&section.text, 1)] {
+				for term in words(text).filter_map(|(_, word)| normalize(word)) {
+					*self
+						.terms
+						.entry(stem(&term))
+						.or_default()
+						.entry(id)
+						.or_default() += weight;
+				}
+			}
+			self.sections.push((page, section));
+		}
+	}
+
+	/// Renders the index as Rust source for `src/search`.
+	pub fn to_rust(&self) -> String {
+		let mut code = String::from("pub const SECTIONS: &[Section] = &[\n");
+		for (page, section) in &self.sections {
+			code.push_str(&format!(
+				"    Section {{ page: {page}, anchor: {:?}, heading: {:?}, text: {:?} }},\n",
+				section.anchor, section.heading, section.text
+			));
+		}
+		code.push_str("];\n\npub const TERMS: &[(&str, &[(u32, u32)])] = &[\n");
+		for (term, postings) in &self.terms {
+			let postings = postings
+				.iter()
+				.map(|(section, weight)| format!("({section}, {weight})"))
+				.collect::<Vec<_>>()
+				.join(", ");
+			code.push_str(&format!("    ({term:?}, &[{postings}]),\n"));
+		}
+		code.push_str("];\n");
+		code
+	}
+}
@@ -0,0 +1,125 @@
+//! Tab groups from fenced divs: a `::: tabs` div whose `::: {.tab title="..."}` children
+//! become the tabs.
+//!
+//! ```markdown
+//! :::: {.tabs group="os"}
+//! ::: {.tab title="Linux"}
+//! ...
+//! :::
+//! ::: {.tab title="Windows"}
+//! ...
+//! :::
+//! ::::
+//! ```
+//!
+//! The build emits the tab list and panels with their ARIA roles, first tab selected; the
+//! app switches tabs and keeps groups sharing a `group` on the same tab.
+
+use serde_json::Value;
+
+use crate::ast::{attribute, div_parts, escape_html, raw_block};
+
+/// A tab being assembled: its title, sync key and panel blocks.
+struct Tab {
+	title: String,
+	key: String,
+	blocks: Vec<Value>,
+}
+
+/// Replaces tab group divs with tab widgets, numbering them for unique element ids.
+#[derive(Default)]
+pub struct TabGroups {
+	count: usize,
+}
+
+impl TabGroups {
+	pub fn transform(&mut self, value: &mut Value) {
+		match value {
+			Value::Array(items) => {
+				let mut transformed = Vec::with_capacity(items.len());
+				for mut item in items.drain(..) {
+					self.transform(&mut item);
+					match self.expand(&item) {
+						Some(blocks) => transformed.extend(blocks),
+						None => transformed.push(item),
+					}
+				}
+				*items = transformed;
+			}
+			Value::Object(map) => {
+				for (_, v) in map.iter_mut() {
+					self.transform(v);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Expands a `.tabs` div into its widget. Blocks between tabs join the preceding tab;
+	/// those before the first tab stay above the tab list.
+	fn expand(&mut self, block: &Value) -> Option<Vec<Value>> {
+		let (_, classes, attr, children) = div_parts(block)?;
+		if !classes.contains(&"tabs") {
+			return None;
+		}
+
+		let mut leading = Vec::new();
+		let mut tabs: Vec<Tab> = Vec::new();
+		for child in children {
+			match div_parts(child).filter(|(_, classes, ..)| classes.contains(&"tab")) {
+				Some((_, _, tab_attr, blocks)) => {
+					let title = attribute(tab_attr, "title")
+						.map(str::to_string)
+						.unwrap_or_else(|| format!("Tab {}", tabs.len() + 1));
+					let key = attribute(tab_attr, "key")
+						.map(str::to_string)
+						.unwrap_or_else(|| title.to_lowercase());
+					tabs.push(Tab {
+						title,
+						key,
+						blocks: blocks.clone(),
+					});
+				}
+				None => match tabs.last_mut() {
+					Some(tab) => tab.blocks.push(child.clone()),
+					None => leading.push(child.clone()),
+				},
+			}
+		}
+		if tabs.is_empty() {
+			return None;
+		}
+
+		self.count += 1;
+		let prefix = format!("tabs-{}", self.count);
+		let mut html = String::from("<div class=\"tabs\"");
+		if let Some(group) = attribute(attr, "group") {
+			html.push_str(&format!(" data-group=\"{}\"", escape_html(group)));
+		}
+		html.push_str("><div class=\"tab-list\" role=\"tablist\">");
+		for (index, tab) in tabs.iter().enumerate() {
+			let selected = index == 0;
+			html.push_str(&format!(
+				"<button type=\"button\" class=\"tab\" role=\"tab\" id=\"{prefix}-tab-{index}\" aria-controls=\"{prefix}-panel-{index}\" aria-selected=\"{selected}\" tabindex=\"{}\" data-tab=\"{}\">{}</button>",
+				if selected { "0" } else { "-1" },
+				escape_html(&tab.key),
+				escape_html(&tab.title),
+			));
+		}
+		html.push_str("</div>");
+
+		let mut blocks = leading;
+		for (index, tab) in tabs.into_iter().enumerate() {
+			html.push_str(&format!(
+				"<div class=\"tab-panel\" role=\"tabpanel\" id=\"{prefix}-panel-{index}\" aria-labelledby=\"{prefix}-tab-{index}\" tabindex=\"0\"{}>",
+				if index == 0 { "" } else { " hidden" }
+			));
+			blocks.push(raw_block(std::mem::take(&mut html)));
+			blocks.extend(tab.blocks);
+			html.push_str("</div>");
+		}
+		html.push_str("</div>");
+		blocks.push(raw_block(html));
+		Some(blocks)
+	}
+}
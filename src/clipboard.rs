@@ -0,0 +1,53 @@
+//! Copying text to the clipboard, in served and file:// builds alike.
+//!
+//! The async Clipboard API is missing or refused in some file:// and embedded contexts;
+//! there the text is copied through a hidden, selected textarea instead.
+
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlDocument, HtmlElement, HtmlTextAreaElement};
+
+/// Copies `text`, returning whether it reached the clipboard.
+pub async fn copy_text(text: &str) -> bool {
+	let clipboard = window().navigator().clipboard();
+	if !clipboard.is_undefined() && JsFuture::from(clipboard.write_text(text)).await.is_ok() {
+		return true;
+	}
+	copy_with_selection(text)
+}
+
+/// Fallback for [`copy_text`]: selects `text` in an off-screen textarea and runs the
+/// legacy copy command.
+fn copy_with_selection(text: &str) -> bool {
+	let document = document();
+	let Some(body) = document.body() else {
+		return false;
+	};
+	let Some(textarea) = document
+		.create_element("textarea")
+		.ok()
+		.and_then(|element| element.dyn_into::<HtmlTextAreaElement>().ok())
+	else {
+		return false;
+	};
+	textarea.set_value(text);
+	let _ = textarea.set_attribute("readonly", "");
+	let _ = textarea.set_attribute("style", "position:fixed;top:0;left:-9999px;opacity:0");
+	if body.append_child(&textarea).is_err() {
+		return false;
+	}
+
+	// Selecting moves focus; give it back afterwards.
+	let focused = document.active_element();
+	textarea.select();
+	let copied = document
+		.dyn_ref::<HtmlDocument>()
+		.and_then(|document| document.exec_command("copy").ok())
+		.unwrap_or(false);
+	textarea.remove();
+	if let Some(focused) = focused.and_then(|element| element.dyn_into::<HtmlElement>().ok()) {
+		let _ = focused.focus();
+	}
+	copied
+}
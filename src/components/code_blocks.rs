@@ -0,0 +1,115 @@
+//! Toolbar for the code blocks of rendered pages: filename, soft-wrap toggle and copy button.
+//!
+//! Page content is static HTML set through `inner_html`, so [`enhance_code_blocks`] adds
+//! the toolbars once the content is mounted. Their buttons are handled by
+//! [`on_code_block_click`], delegated from the page's article: no listener is attached to
+//! the blocks themselves, so nothing is left behind when the route replaces the content.
+
+use std::time::Duration;
+
+use leptos::ev::MouseEvent;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::wasm_bindgen::JsCast;
+use web_sys::Element;
+
+use crate::clipboard::copy_text;
+
+/// How long the copy button reports its result before reverting.
+const FEEDBACK_DURATION: Duration = Duration::from_secs(2);
+
+/// Wraps every `pre > code` under `container` in a `.code-block` with a toolbar.
+/// Blocks with a `data-title` (from the fence's `title` attribute) get a filename header.
+pub fn enhance_code_blocks(container: &Element) {
+	let Ok(codes) = container.query_selector_all("pre > code") else {
+		return;
+	};
+	for index in 0..codes.length() {
+		let Some(pre) = codes
+			.item(index)
+			.and_then(|code| code.parent_element())
+			.filter(|pre| {
+				!pre.parent_element()
+					.is_some_and(|parent| parent.class_list().contains("code-block"))
+			})
+		else {
+			continue;
+		};
+		let _ = wrap_code_block(&pre);
+	}
+}
+
+fn wrap_code_block(pre: &Element) -> Option<()> {
+	let document = document();
+	let element = |tag: &str, class: &str| {
+		let element = document.create_element(tag).ok()?;
+		element.set_class_name(class);
+		Some(element)
+	};
+	let button = |class: &str, label: &str, title: &str| {
+		let button = element("button", class)?;
+		let _ = button.set_attribute("type", "button");
+		let _ = button.set_attribute("title", title);
+		button.set_text_content(Some(label));
+		Some(button)
+	};
+
+	let block = element("div", "code-block")?;
+	let header = element("div", "code-header")?;
+	if let Some(title) = pre.get_attribute("data-title") {
+		let _ = block.class_list().add_1("has-title");
+		let name = element("span", "code-title")?;
+		name.set_text_content(Some(&title));
+		header.append_child(&name).ok()?;
+	}
+	let wrap = button("code-wrap", "Wrap", "Toggle line wrapping")?;
+	let _ = wrap.set_attribute("aria-pressed", "false");
+	header.append_child(&wrap).ok()?;
+	let copy = button("code-copy", "Copy", "Copy code")?;
+	header.append_child(&copy).ok()?;
+
+	pre.parent_node()?.insert_before(&block, Some(pre)).ok()?;
+	block.append_child(&header).ok()?;
+	block.append_child(pre).ok()?;
+	Some(())
+}
+
+/// Handles clicks on code block toolbar buttons; other clicks are left alone.
+pub fn on_code_block_click(ev: &MouseEvent) {
+	let Some(button) = ev
+		.target()
+		.and_then(|target| target.dyn_into::<Element>().ok())
+		.and_then(|target| target.closest(".code-block button").ok().flatten())
+	else {
+		return;
+	};
+	let Some(pre) = button
+		.closest(".code-block")
+		.ok()
+		.flatten()
+		.and_then(|block| block.query_selector("pre").ok().flatten())
+	else {
+		return;
+	};
+
+	if button.class_list().contains("code-wrap") {
+		let wrapped = pre.class_list().toggle("wrapped").unwrap_or(false);
+		let _ = button.set_attribute("aria-pressed", &wrapped.to_string());
+	} else if button.class_list().contains("code-copy") {
+		let text = pre.text_content().unwrap_or_default();
+		spawn_local(async move {
+			let copied = copy_text(&text).await;
+			button.set_text_content(Some(if copied { "Copied" } else { "Copy failed" }));
+			let _ = button
+				.class_list()
+				.add_1(if copied { "copied" } else { "failed" });
+			set_timeout(
+				move || {
+					button.set_text_content(Some("Copy"));
+					let _ = button.class_list().remove_2("copied", "failed");
+				},
+				FEEDBACK_DURATION,
+			);
+		});
+	}
+}
@@ -0,0 +1,365 @@
+//! Command palette (Ctrl+K) for jumping to pages, categories, headings and actions.
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::{ev, html};
+use leptos_router::hooks::{use_location, use_navigate};
+use web_sys::{ScrollIntoViewOptions, ScrollLogicalPosition};
+
+use crate::clipboard::copy_text;
+use crate::components::toc::heading_in_view;
+use crate::content::{find_page, Page, PAGES};
+use crate::navigation::{get_navigation, neighbours, reading_order, Category, NavItem};
+use crate::search::fuzzy_score;
+use crate::theme::use_theme;
+
+/// Maximum number of commands listed at once.
+const MAX_COMMANDS: usize = 50;
+
+/// Palette commands that act on the app rather than navigate to content.
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+	ToggleTheme,
+	CopySectionLink,
+	NextPage,
+	PreviousPage,
+}
+
+impl Action {
+	const ALL: [Action; 4] = [
+		Action::ToggleTheme,
+		Action::CopySectionLink,
+		Action::NextPage,
+		Action::PreviousPage,
+	];
+
+	fn label(self) -> &'static str {
+		match self {
+			Action::ToggleTheme => "Switch theme (light, dark, system)",
+			Action::CopySectionLink => "Copy link to section",
+			Action::NextPage => "Go to next page",
+			Action::PreviousPage => "Go to previous page",
+		}
+	}
+}
+
+/// An entry in the palette.
+#[derive(Clone, Copy)]
+enum Command {
+	Page(&'static Page),
+	/// A sidebar category, opening its index or first page.
+	Category {
+		name: &'static str,
+		page: &'static Page,
+	},
+	Heading {
+		page: &'static Page,
+		id: &'static str,
+		text: &'static str,
+	},
+	Action(Action),
+}
+
+impl Command {
+	fn label(self) -> &'static str {
+		match self {
+			Command::Page(page) => page.title,
+			Command::Category { name, .. } => name,
+			Command::Heading { text, .. } => text,
+			Command::Action(action) => action.label(),
+		}
+	}
+
+	/// Secondary text shown next to the label, also matched at a lower weight.
+	fn detail(self) -> &'static str {
+		match self {
+			Command::Page(page) => page.category,
+			Command::Category { .. } => "Category",
+			Command::Heading { page, .. } => page.title,
+			Command::Action(_) => "Action",
+		}
+	}
+
+	fn kind(self) -> &'static str {
+		match self {
+			Command::Page(_) => "page",
+			Command::Category { .. } => "category",
+			Command::Heading { .. } => "heading",
+			Command::Action(_) => "action",
+		}
+	}
+}
+
+/// Every command, actions first, then pages in reading order, categories and headings.
+fn all_commands() -> Vec<Command> {
+	let mut commands: Vec<Command> = Action::ALL.into_iter().map(Command::Action).collect();
+	commands.extend(reading_order().into_iter().map(Command::Page));
+	push_categories(get_navigation(), &mut commands);
+	commands.extend(PAGES.iter().flat_map(|page| {
+		page.toc.iter().map(move |entry| Command::Heading {
+			page,
+			id: entry.id,
+			text: entry.text,
+		})
+	}));
+	commands
+}
+
+fn push_categories(categories: Vec<Category>, commands: &mut Vec<Command>) {
+	for category in categories {
+		if let Some(page) = category.index.or_else(|| first_page(&category.items)) {
+			commands.push(Command::Category {
+				name: category.name,
+				page,
+			});
+		}
+		let nested = category.items.into_iter().filter_map(|item| match item {
+			NavItem::Category(category) => Some(category),
+			NavItem::Page(_) => None,
+		});
+		push_categories(nested.collect(), commands);
+	}
+}
+
+fn first_page(items: &[NavItem]) -> Option<&'static Page> {
+	items.iter().find_map(|item| match item {
+		NavItem::Page(page) => Some(*page),
+		NavItem::Category(category) => category.index.or_else(|| first_page(&category.items)),
+	})
+}
+
+/// Copies the current URL, pointing at the section in view, to the clipboard.
+fn copy_section_link(page: &Page) {
+	let window = window();
+	let Ok(href) = window.location().href() else {
+		return;
+	};
+	// Works for served and file:// builds alike, as only the fragment changes.
+	let base = href.split('#').next().unwrap_or(&href);
+	let link = match heading_in_view(page.toc) {
+		Some(id) => format!("{base}#{id}"),
+		None => base.to_string(),
+	};
+	spawn_local(async move {
+		copy_text(&link).await;
+	});
+}
+
+fn scroll_to_heading(id: &str) {
+	if let Some(heading) = document().get_element_by_id(id) {
+		heading.scroll_into_view();
+	}
+}
+
+/// Modal palette opened with Ctrl+K (Cmd+K on macOS) that fuzzy-matches page titles,
+/// categories and headings, plus a few actions on the current page.
+#[component]
+pub fn CommandPalette() -> impl IntoView {
+	let (open, set_open) = signal(false);
+	let (query, set_query) = signal(String::new());
+	let (selected, set_selected) = signal(0usize);
+	let input = NodeRef::<html::Input>::new();
+	let commands = StoredValue::new(all_commands());
+	let pathname = use_location().pathname;
+	let navigate = use_navigate();
+	let theme = use_theme();
+
+	let current_page = move || pathname.get().strip_prefix("/docs/").and_then(find_page);
+	let available = move |command: Command| match command {
+		Command::Action(Action::ToggleTheme) => true,
+		Command::Action(Action::CopySectionLink) => current_page().is_some(),
+		Command::Action(Action::NextPage) => current_page().and_then(|p| neighbours(p).1).is_some(),
+		Command::Action(Action::PreviousPage) => {
+			current_page().and_then(|p| neighbours(p).0).is_some()
+		}
+		_ => true,
+	};
+
+	// Indices into `commands`, best matches first; label matches outrank detail matches.
+	let matches = Memo::new(move |_| {
+		let query = query.get();
+		commands.with_value(|commands| {
+			let mut scored: Vec<(u32, usize)> = commands
+				.iter()
+				.enumerate()
+				.filter(|(_, command)| available(**command))
+				.filter_map(|(index, command)| {
+					if query.trim().is_empty() {
+						// Without a query, list actions and pages but not every heading.
+						return (!matches!(command, Command::Heading { .. })).then_some((0, index));
+					}
+					let label = fuzzy_score(&query, command.label()).map(|score| score * 2);
+					let detail = fuzzy_score(&query, command.detail());
+					Some((label.max(detail)?, index))
+				})
+				.collect();
+			// Ties go to shorter labels; an empty query keeps the listing order.
+			if !query.trim().is_empty() {
+				scored.sort_by(|a, b| {
+					b.0.cmp(&a.0).then_with(|| {
+						commands[a.1]
+							.label()
+							.len()
+							.cmp(&commands[b.1].label().len())
+					})
+				});
+			}
+			scored
+				.into_iter()
+				.take(MAX_COMMANDS)
+				.map(|(_, index)| index)
+				.collect::<Vec<_>>()
+		})
+	});
+
+	let run = move |index: usize| {
+		let Some(command) = commands.with_value(|commands| commands.get(index).copied()) else {
+			return;
+		};
+		set_open.set(false);
+		let go = |page: &Page| navigate(&format!("/docs/{}", page.slug), Default::default());
+		match command {
+			Command::Page(page) | Command::Category { page, .. } => go(page),
+			Command::Heading { page, id, .. } => {
+				navigate(&format!("/docs/{}#{id}", page.slug), Default::default());
+				request_animation_frame(move || scroll_to_heading(id));
+			}
+			Command::Action(Action::ToggleTheme) => theme.cycle(),
+			Command::Action(Action::CopySectionLink) => {
+				if let Some(page) = current_page() {
+					copy_section_link(page);
+				}
+			}
+			Command::Action(Action::NextPage) => {
+				if let Some(page) = current_page().and_then(|page| neighbours(page).1) {
+					go(page);
+				}
+			}
+			Command::Action(Action::PreviousPage) => {
+				if let Some(page) = current_page().and_then(|page| neighbours(page).0) {
+					go(page);
+				}
+			}
+		}
+	};
+
+	let handle = window_event_listener(ev::keydown, move |ev| {
+		if (ev.ctrl_key() || ev.meta_key()) && ev.key().eq_ignore_ascii_case("k") {
+			ev.prevent_default();
+			set_open.update(|open| *open = !*open);
+		}
+	});
+	on_cleanup(move || handle.remove());
+
+	// Start from a clean slate and focus the input whenever the palette opens.
+	Effect::new(move |_| {
+		if open.get() {
+			set_query.set(String::new());
+			set_selected.set(0);
+			request_animation_frame(move || {
+				if let Some(input) = input.get_untracked() {
+					let _ = input.focus();
+				}
+			});
+		}
+	});
+
+	// Keep the selected command visible while moving through a long list.
+	Effect::new(move |_| {
+		selected.track();
+		request_animation_frame(|| {
+			if let Ok(Some(item)) = document().query_selector(".palette-item.selected") {
+				let options = ScrollIntoViewOptions::new();
+				options.set_block(ScrollLogicalPosition::Nearest);
+				item.scroll_into_view_with_scroll_into_view_options(&options);
+			}
+		});
+	});
+
+	let run_selected = run.clone();
+	let on_keydown = move |ev: ev::KeyboardEvent| {
+		let count = matches.with_untracked(Vec::len);
+		match ev.key().as_str() {
+			"ArrowDown" if count > 0 => {
+				ev.prevent_default();
+				set_selected.update(|i| *i = (*i + 1) % count);
+			}
+			"ArrowUp" if count > 0 => {
+				ev.prevent_default();
+				set_selected.update(|i| *i = (*i + count - 1) % count);
+			}
+			"Enter" => {
+				ev.prevent_default();
+				let index = matches
+					.with_untracked(|matches| matches.get(selected.get_untracked()).copied());
+				if let Some(index) = index {
+					run_selected(index);
+				}
+			}
+			"Escape" => set_open.set(false),
+			_ => {}
+		}
+	};
+
+	view! {
+		<Show when=move || open.get()>
+			<div class="palette-backdrop" on:click=move |_| set_open.set(false)>
+				<div
+					class="palette"
+					role="dialog"
+					aria-modal="true"
+					aria-label="Command palette"
+					on:click=|ev| ev.stop_propagation()
+				>
+					<input
+						node_ref=input
+						type="text"
+						class="palette-input"
+						placeholder="Jump to a page, heading or action"
+						aria-label="Command"
+						prop:value=query
+						on:input=move |ev| {
+							set_query.set(event_target_value(&ev));
+							set_selected.set(0);
+						}
+						on:keydown=on_keydown.clone()
+					/>
+					<ul class="palette-results" role="listbox">
+						{
+							let run = run.clone();
+							move || {
+								let matches = matches.get();
+								if matches.is_empty() {
+									return view! { <li class="palette-empty">"No matches"</li> }.into_any();
+								}
+								matches
+									.into_iter()
+									.enumerate()
+									.map(|(position, index)| {
+										let run = run.clone();
+										let command = commands.with_value(|commands| commands[index]);
+										view! {
+											<li
+												class=format!("palette-item palette-{}", command.kind())
+												class:selected=move || selected.get() == position
+												role="option"
+												aria-selected=move || (selected.get() == position).to_string()
+												on:mouseenter=move |_| set_selected.set(position)
+												on:click=move |_| run(index)
+											>
+												<span class="palette-label">{command.label()}</span>
+												<span class="palette-detail">{command.detail()}</span>
+											</li>
+										}
+									})
+									.collect_view()
+									.into_any()
+							}
+						}
+					</ul>
+					<p class="palette-hint">"↑↓ to move, Enter to select, Esc to close"</p>
+				</div>
+			</div>
+		</Show>
+	}
+}
@@ -0,0 +1,54 @@
+//! Links inside rendered page content: same-page `#fragment` links, such as heading anchors
+//! and footnotes, and links to other pages.
+//!
+//! Page content is static HTML set through `inner_html`, so its links bypass the router's
+//! `<A>` handling. Both kinds navigate through the router instead: pages load without a full
+//! reload, and fragments survive in the file:// build, which keeps its route in the URL. As
+//! fragment targets only exist once the content is mounted, the page scrolls to the router's
+//! fragment itself.
+
+use leptos::ev::MouseEvent;
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use web_sys::{Element, ScrollBehavior, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+/// A link in page content that the app follows itself.
+pub enum ContentLink {
+	/// A `#fragment` on the current page, without the `#`.
+	Fragment(String),
+	/// A route of the app: the home page or a page such as `/docs/rust/lists#syntax`.
+	Route(String),
+}
+
+/// Scrolls the element with `id` to the top of the viewport, if it exists.
+pub fn scroll_to_fragment(id: &str, behavior: ScrollBehavior) {
+	if let Some(target) = document().get_element_by_id(id) {
+		let options = ScrollIntoViewOptions::new();
+		options.set_behavior(behavior);
+		options.set_block(ScrollLogicalPosition::Start);
+		target.scroll_into_view_with_scroll_into_view_options(&options);
+	}
+}
+
+/// Returns the link followed by a plain left click, if it stays within the app.
+/// Modified clicks and links with a `target` or `download` are left to the browser.
+pub fn content_link(ev: &MouseEvent) -> Option<ContentLink> {
+	if ev.button() != 0 || ev.ctrl_key() || ev.meta_key() || ev.shift_key() || ev.alt_key() {
+		return None;
+	}
+	let link = ev
+		.target()?
+		.dyn_into::<Element>()
+		.ok()?
+		.closest("a[href]")
+		.ok()??;
+	if link.has_attribute("target") || link.has_attribute("download") {
+		return None;
+	}
+	let href = link.get_attribute("href")?;
+	if let Some(id) = href.strip_prefix('#') {
+		return (!id.is_empty()).then(|| ContentLink::Fragment(id.to_string()));
+	}
+	// Other absolute links, such as extracted media under `/assets/`, are real files.
+	(href == "/" || href.starts_with("/docs/")).then_some(ContentLink::Route(href))
+}
@@ -1,16 +1,155 @@
 //! Layout wrapper for documentation pages with sidebar.
 
 use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use leptos::{ev, html};
+use leptos_router::hooks::use_location;
+use web_sys::HtmlElement;
 
 use crate::components::sidebar::Sidebar;
+use crate::components::toc::TableOfContents;
+use crate::content::TocEntry;
+use crate::site::SITE_NAME;
 
-/// Layout component that wraps documentation content with a sidebar.
+/// Viewports where the sidebar becomes an off-canvas drawer; keep in sync with styles.scss.
+const DRAWER_QUERY: &str = "(max-width: 768px)";
+
+/// Elements inside the drawer that take keyboard focus.
+const FOCUSABLE: &str = "a[href], button, input, summary, [tabindex]:not([tabindex=\"-1\"])";
+
+/// Whether the viewport is narrow enough for the sidebar to be shown as a drawer.
+fn is_drawer_layout() -> bool {
+	window()
+		.match_media(DRAWER_QUERY)
+		.ok()
+		.flatten()
+		.is_some_and(|query| query.matches())
+}
+
+/// Visible focusable elements inside `container`, in document order.
+fn focusable_elements(container: &HtmlElement) -> Vec<HtmlElement> {
+	let Ok(nodes) = container.query_selector_all(FOCUSABLE) else {
+		return Vec::new();
+	};
+	(0..nodes.length())
+		.filter_map(|index| nodes.item(index)?.dyn_into::<HtmlElement>().ok())
+		.filter(|element| element.offset_parent().is_some())
+		.collect()
+}
+
+/// Layout component that wraps documentation content with a sidebar and table of contents.
+///
+/// On small screens the sidebar turns into a drawer opened from a sticky header bar. The
+/// drawer closes on navigation or Escape and keeps keyboard focus inside while open.
 #[component]
-pub fn DocsLayout(children: Children) -> impl IntoView {
+pub fn DocsLayout(
+	/// Title of the current page, shown in the small-screen header bar.
+	#[prop(into, optional)]
+	title: Signal<Option<&'static str>>,
+	/// Headings of the current page for the "On this page" panel.
+	#[prop(into, optional)]
+	toc: Signal<&'static [TocEntry]>,
+	children: Children,
+) -> impl IntoView {
+	let (drawer_open, set_drawer_open) = signal(false);
+	let drawer = NodeRef::<html::Div>::new();
+	let toggle = NodeRef::<html::Button>::new();
+	let pathname = use_location().pathname;
+
+	// Following a link in the drawer leaves it behind.
+	Effect::new(move |_| {
+		pathname.track();
+		set_drawer_open.set(false);
+	});
+
+	// Move focus into the drawer when it opens and back to the toggle when it closes.
+	Effect::new(move |was_open: Option<bool>| {
+		let open = drawer_open.get();
+		if open {
+			request_animation_frame(move || {
+				if let Some(drawer) = drawer.get_untracked() {
+					let _ = drawer.focus();
+				}
+			});
+		} else if was_open == Some(true)
+			&& let Some(toggle) = toggle.get_untracked()
+		{
+			let _ = toggle.focus();
+		}
+		open
+	});
+
+	let handle = window_event_listener(ev::keydown, move |ev| {
+		if !drawer_open.get_untracked() {
+			return;
+		}
+		// The viewport grew past the breakpoint; the sidebar is no longer a drawer.
+		if !is_drawer_layout() {
+			set_drawer_open.set(false);
+			return;
+		}
+		match ev.key().as_str() {
+			"Escape" => set_drawer_open.set(false),
+			"Tab" => {
+				let Some(drawer) = drawer.get_untracked() else {
+					return;
+				};
+				let focusable = focusable_elements(&drawer);
+				let (Some(first), Some(last)) = (focusable.first(), focusable.last()) else {
+					ev.prevent_default();
+					return;
+				};
+				let active = document().active_element();
+				let is_active = |element: &HtmlElement| active.as_ref() == Some(element.as_ref());
+				let inside = active
+					.as_ref()
+					.is_some_and(|active| drawer.contains(Some(active)));
+				if ev.shift_key() && (is_active(first) || !inside || is_active(&drawer)) {
+					ev.prevent_default();
+					let _ = last.focus();
+				} else if !ev.shift_key() && (is_active(last) || !inside) {
+					ev.prevent_default();
+					let _ = first.focus();
+				}
+			}
+			_ => {}
+		}
+	});
+	on_cleanup(move || handle.remove());
+
 	view! {
-		<div class="docs-layout">
-			<Sidebar />
+		<div class="docs-layout" class:drawer-open=move || drawer_open.get()>
+			<header class="mobile-header">
+				<button
+					class="drawer-toggle"
+					node_ref=toggle
+					aria-label="Open navigation"
+					aria-controls="sidebar-drawer"
+					aria-expanded=move || drawer_open.get().to_string()
+					on:click=move |_| set_drawer_open.update(|open| *open = !*open)
+				>
+					<span class="drawer-toggle-icon" aria-hidden="true" />
+				</button>
+				<span class="mobile-header-title">{move || title.get().unwrap_or(SITE_NAME)}</span>
+			</header>
+			<div
+				class="drawer-backdrop"
+				aria-hidden="true"
+				on:click=move |_| set_drawer_open.set(false)
+			/>
+			<div
+				id="sidebar-drawer"
+				class="sidebar-drawer"
+				node_ref=drawer
+				tabindex="-1"
+				role=move || drawer_open.get().then_some("dialog")
+				aria-modal=move || drawer_open.get().then_some("true")
+				aria-label="Navigation"
+			>
+				<Sidebar />
+			</div>
 			<main class="docs-content">{children()}</main>
+			<TableOfContents entries=toc />
 		</div>
 	}
 }
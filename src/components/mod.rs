@@ -1,3 +1,13 @@
+pub mod code_blocks;
+pub mod command_palette;
+pub mod content_links;
 pub mod counter_btn;
 pub mod docs_layout;
+pub mod page_nav;
+pub mod progress_dashboard;
+pub mod quiz;
+pub mod search;
 pub mod sidebar;
+pub mod tabs;
+pub mod theme_toggle;
+pub mod toc;
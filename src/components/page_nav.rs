@@ -0,0 +1,102 @@
+//! Previous/next links for reading pages in navigation order.
+
+use leptos::ev;
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use leptos_router::components::A;
+use leptos_router::hooks::use_navigate;
+use web_sys::Element;
+
+use crate::content::Page;
+use crate::navigation::neighbours;
+
+/// Elements that use the arrow keys themselves: form fields, editable text and ARIA widgets
+/// such as tab lists.
+const ARROW_KEY_WIDGETS: &str = "input, textarea, select, [contenteditable], [role=tablist], \
+	[role=tab], [role=listbox], [role=menu], [role=menubar], [role=radiogroup], [role=slider], \
+	[role=tree], [role=grid]";
+
+/// Whether the arrow keys belong to the element a key press happened in: a field or widget,
+/// or a focused block that scrolls sideways, such as a wide code block or table.
+fn arrows_taken(ev: &ev::KeyboardEvent) -> bool {
+	let Some(target) = ev
+		.target()
+		.and_then(|target| target.dyn_into::<Element>().ok())
+	else {
+		return false;
+	};
+	if target.closest(ARROW_KEY_WIDGETS).ok().flatten().is_some() {
+		return true;
+	}
+	// The page itself is not a block; a key press outside any focused element targets `body`.
+	std::iter::successors(Some(target), |element| element.parent_element())
+		.take_while(|element| !matches!(element.tag_name().as_str(), "BODY" | "HTML"))
+		.any(|element| element.scroll_width() > element.client_width())
+}
+
+/// "Previous" and "Next" links below a page, also bound to the left and right arrow keys
+/// unless focus is in a field, a widget or a block that scrolls sideways.
+#[component]
+pub fn PageNav(#[prop(into)] page: Signal<Option<&'static Page>>) -> impl IntoView {
+	let links = move || page.get().map(neighbours).unwrap_or_default();
+	let navigate = use_navigate();
+
+	let handle = window_event_listener(ev::keydown, move |ev| {
+		let modified = ev.ctrl_key() || ev.meta_key() || ev.alt_key() || ev.shift_key();
+		// Widgets handling arrow keys themselves prevent the default action.
+		if modified || ev.default_prevented() || arrows_taken(&ev) {
+			return;
+		}
+		let Some(page) = page.get_untracked() else {
+			return;
+		};
+		let (prev, next) = neighbours(page);
+		let target = match ev.key().as_str() {
+			"ArrowLeft" => prev,
+			"ArrowRight" => next,
+			_ => None,
+		};
+		if let Some(target) = target {
+			navigate(&format!("/docs/{}", target.slug), Default::default());
+		}
+	});
+	on_cleanup(move || handle.remove());
+
+	move || {
+		let (prev, next) = links();
+		(prev.is_some() || next.is_some()).then(|| {
+			view! {
+				<nav class="page-nav" aria-label="Previous and next pages">
+					{prev
+						.map(|page| {
+							view! {
+								<A
+									href=format!("/docs/{}", page.slug)
+									attr:class="page-nav-link page-nav-prev"
+									attr:rel="prev"
+									attr:title="Previous page (←)"
+								>
+									<span class="page-nav-label">"← Previous"</span>
+									<span class="page-nav-title">{page.title}</span>
+								</A>
+							}
+						})}
+					{next
+						.map(|page| {
+							view! {
+								<A
+									href=format!("/docs/{}", page.slug)
+									attr:class="page-nav-link page-nav-next"
+									attr:rel="next"
+									attr:title="Next page (→)"
+								>
+									<span class="page-nav-label">"Next →"</span>
+									<span class="page-nav-title">{page.title}</span>
+								</A>
+							}
+						})}
+				</nav>
+			}
+		})
+	}
+}
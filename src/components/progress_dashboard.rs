@@ -0,0 +1,162 @@
+//! Overview of the learner's progress, with export to and import from a JSON file.
+
+use leptos::ev;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::components::A;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+use crate::content::Page;
+use crate::navigation::{get_navigation, reading_order};
+use crate::progress::{use_progress, Progress};
+
+/// File name suggested when exporting progress.
+const EXPORT_FILE: &str = "bezel-progress.json";
+
+/// Percent-encodes `text` for a `data:` URL.
+fn percent_encode(text: &str) -> String {
+	text.bytes()
+		.map(|byte| match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+				char::from(byte).to_string()
+			}
+			_ => format!("%{byte:02X}"),
+		})
+		.collect()
+}
+
+/// Completion of every page and category, quizzes passed and a link to continue reading.
+#[component]
+pub fn ProgressDashboard() -> impl IntoView {
+	let progress = use_progress();
+	let pages = StoredValue::new(reading_order());
+	let categories: Vec<(&'static str, Vec<&'static Page>)> = get_navigation()
+		.iter()
+		.map(|category| (category.name, category.pages()))
+		.collect();
+	let quiz_count: usize = pages.with_value(|pages| pages.iter().map(|p| p.quizzes.len()).sum());
+	let (status, set_status) = signal(None::<String>);
+
+	let overall = move || pages.with_value(|pages| progress.with(|p| p.percent_complete(pages)));
+	let completed = move || {
+		pages.with_value(|pages| {
+			progress.with(|p| pages.iter().filter(|page| p.is_complete(page)).count())
+		})
+	};
+	let visited = move || {
+		pages.with_value(|pages| {
+			progress.with(|p| {
+				pages
+					.iter()
+					.filter(|page| p.page(page).is_some_and(|page| page.visited))
+					.count()
+			})
+		})
+	};
+	let quizzes_passed = move || {
+		pages.with_value(|pages| {
+			progress.with(|p| {
+				pages
+					.iter()
+					.flat_map(|page| page.quizzes.iter().map(move |quiz| (page, quiz)))
+					.filter(|(page, quiz)| p.quiz_passed(page, quiz))
+					.count()
+			})
+		})
+	};
+	let next_page = move || {
+		pages.with_value(|pages| {
+			progress.with(|p| pages.iter().copied().find(|page| !p.is_complete(page)))
+		})
+	};
+	let export_href = move || {
+		let json = progress.with(Progress::to_json);
+		format!(
+			"data:application/json;charset=utf-8,{}",
+			percent_encode(&json)
+		)
+	};
+
+	let on_import = move |ev: ev::Event| {
+		let input = event_target::<HtmlInputElement>(&ev);
+		let Some(file) = input.files().and_then(|files| files.get(0)) else {
+			return;
+		};
+		// Clear the field so that importing the same file again fires `change`.
+		input.set_value("");
+		spawn_local(async move {
+			let text = JsFuture::from(file.text())
+				.await
+				.ok()
+				.and_then(|text| text.as_string());
+			let message = match text.as_deref().map(Progress::from_json) {
+				Some(Ok(imported)) => {
+					progress.import(imported);
+					"Progress imported.".to_string()
+				}
+				Some(Err(err)) => format!("Could not import progress: {err}"),
+				None => "Could not read the file.".to_string(),
+			};
+			set_status.set(Some(message));
+		});
+	};
+
+	let total = pages.with_value(Vec::len);
+	view! {
+		<section class="progress-dashboard">
+			<h2>"Your progress"</h2>
+			<div class="progress-overall">
+				<progress class="progress-bar" max="100" value=overall></progress>
+				<span>{overall} "% complete"</span>
+			</div>
+			<ul class="progress-stats">
+				<li>{completed} " of " {total} " pages completed"</li>
+				<li>{visited} " of " {total} " pages visited"</li>
+				{(quiz_count > 0)
+					.then(|| view! { <li>{quizzes_passed} " of " {quiz_count} " quizzes passed"</li> })}
+			</ul>
+			{move || {
+				next_page()
+					.map(|page| {
+						view! {
+							<p class="progress-continue">
+								"Continue with " <A href=format!("/docs/{}", page.slug)>{page.title}</A>
+							</p>
+						}
+					})
+			}}
+			<ul class="progress-categories">
+				{categories
+					.into_iter()
+					.map(|(name, pages)| {
+						let count = pages.len();
+						let pages = StoredValue::new(pages);
+						let percent = move || {
+							pages.with_value(|pages| progress.with(|p| p.percent_complete(pages)))
+						};
+						view! {
+							<li>
+								<span class="progress-category-name">{name}</span>
+								<progress class="progress-bar" max="100" value=percent></progress>
+								<span class="progress-category-percent">
+									{percent} "% of " {count} " pages"
+								</span>
+							</li>
+						}
+					})
+					.collect_view()}
+			</ul>
+			<div class="progress-actions">
+				<a class="progress-export" href=export_href download=EXPORT_FILE>
+					"Export progress"
+				</a>
+				<label class="progress-import">
+					"Import progress"
+					<input type="file" accept=".json,application/json" on:change=on_import />
+				</label>
+			</div>
+			{move || status.get().map(|status| view! { <p class="progress-status">{status}</p> })}
+		</section>
+	}
+}
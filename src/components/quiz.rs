@@ -0,0 +1,350 @@
+//! Quizzes compiled by the build from `.quiz` divs, graded in the browser.
+//!
+//! Each quiz is mounted as a component island. Learners answer, check their answers and see
+//! per-question feedback and explanations; the best score is recorded in the learner's
+//! progress.
+
+use leptos::ev::SubmitEvent;
+use leptos::prelude::*;
+
+use crate::content::{find_page, Answer, Page, Question, Quiz};
+use crate::progress::{use_progress, QuizResult};
+
+/// Lowercases `text` and collapses its whitespace, for comparing short answers.
+fn normalize(text: &str) -> String {
+	text.split_whitespace()
+		.collect::<Vec<_>>()
+		.join(" ")
+		.to_lowercase()
+}
+
+/// Whether the short answer `text` matches `pattern`, where `*` matches any text.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+	let (pattern, text) = (normalize(pattern), normalize(text));
+	let mut parts = pattern.split('*');
+	let Some(mut rest) = text.strip_prefix(parts.next().unwrap_or_default()) else {
+		return false;
+	};
+	let parts: Vec<&str> = parts.collect();
+	let Some((last, middle)) = parts.split_last() else {
+		return rest.is_empty();
+	};
+	for part in middle {
+		let Some(start) = rest.find(part) else {
+			return false;
+		};
+		rest = &rest[start + part.len()..];
+	}
+	rest.ends_with(last)
+}
+
+/// A learner's answer to one question: the chosen options and the typed text.
+#[derive(Clone, Copy)]
+struct Response {
+	selected: RwSignal<Vec<usize>>,
+	text: RwSignal<String>,
+}
+
+impl Response {
+	fn new() -> Self {
+		Response {
+			selected: RwSignal::new(Vec::new()),
+			text: RwSignal::new(String::new()),
+		}
+	}
+
+	fn is_correct(self, question: &Question) -> bool {
+		self.text.with_untracked(|text| {
+			self.selected
+				.with_untracked(|selected| is_correct(question, selected, text))
+		})
+	}
+}
+
+/// Whether choosing the options at `selected` (True and False being 0 and 1) or typing
+/// `text` answers `question` correctly.
+fn is_correct(question: &Question, selected: &[usize], text: &str) -> bool {
+	match question.answer {
+		Answer::Single(choices) | Answer::Multiple(choices) => choices
+			.iter()
+			.enumerate()
+			.all(|(index, choice)| choice.correct == selected.contains(&index)),
+		Answer::TrueFalse(answer) => selected == [usize::from(!answer)],
+		Answer::Short(patterns) => {
+			!text.trim().is_empty()
+				&& patterns
+					.iter()
+					.any(|pattern| matches_pattern(pattern, text))
+		}
+	}
+}
+
+/// The quiz `quiz` of the page `page`, by slug and id as the build's placeholder names
+/// them. Renders nothing if either is unknown.
+#[component]
+pub fn Quiz(page: String, quiz: String) -> impl IntoView {
+	let found =
+		find_page(&page).and_then(|page| Some((page, page.quizzes.iter().find(|q| q.id == quiz)?)));
+	found.map(|(page, quiz)| view! { <QuizForm page quiz /> })
+}
+
+#[component]
+fn QuizForm(page: &'static Page, quiz: &'static Quiz) -> impl IntoView {
+	let responses: Vec<Response> = quiz.questions.iter().map(|_| Response::new()).collect();
+	// Whether each question was answered correctly, once the quiz is checked.
+	let graded = RwSignal::new(None::<Vec<bool>>);
+	let progress = use_progress();
+	let best = move || progress.with(|progress| progress.quiz(page, quiz));
+	let checked = move || graded.with(Option::is_some);
+
+	let grade = {
+		let responses = responses.clone();
+		move |ev: SubmitEvent| {
+			ev.prevent_default();
+			let correct: Vec<bool> = quiz
+				.questions
+				.iter()
+				.zip(&responses)
+				.map(|(question, response)| response.is_correct(question))
+				.collect();
+			let result = QuizResult {
+				score: correct.iter().filter(|correct| **correct).count(),
+				total: correct.len(),
+			};
+			progress.record_quiz(page, quiz, result);
+			graded.set(Some(correct));
+		}
+	};
+
+	let summary = move || {
+		let correct = graded.get()?;
+		let result = QuizResult {
+			score: correct.iter().filter(|correct| **correct).count(),
+			total: correct.len(),
+		};
+		let verdict = if result.passed(quiz) {
+			"passed".to_string()
+		} else {
+			format!("{}% needed to pass", quiz.pass)
+		};
+		Some(format!(
+			"{} of {} correct, {verdict}",
+			result.score, result.total
+		))
+	};
+
+	let questions = quiz
+		.questions
+		.iter()
+		.zip(responses)
+		.enumerate()
+		.map(|(index, (question, response))| {
+			let outcome = move || graded.with(|graded| graded.as_ref().map(|g| g[index]));
+			view! {
+				<li
+					class="quiz-question"
+					class:correct=move || outcome() == Some(true)
+					class:incorrect=move || outcome() == Some(false)
+				>
+					<div class="quiz-prompt" inner_html=question.prompt />
+					<QuestionInput
+						question
+						response
+						name=format!("{}-{index}", quiz.id)
+						disabled=Signal::derive(checked)
+					/>
+					{move || {
+						outcome()
+							.map(|correct| {
+								view! {
+									<p class="quiz-feedback">
+										{if correct { "Correct" } else { "Not quite" }}
+									</p>
+									{question
+										.explanation
+										.map(|html| view! { <div class="quiz-explanation" inner_html=html /> })}
+								}
+							})
+					}}
+				</li>
+			}
+		})
+		.collect_view();
+
+	view! {
+		<form class="quiz" on:submit=grade>
+			<ol class="quiz-questions">{questions}</ol>
+			<div class="quiz-footer">
+				<Show
+					when=checked
+					fallback=|| view! { <button type="submit" class="quiz-check">"Check answers"</button> }
+				>
+					<p class="quiz-summary">{summary}</p>
+					<button type="button" class="quiz-retry" on:click=move |_| graded.set(None)>
+						"Try again"
+					</button>
+				</Show>
+				{move || {
+					best()
+						.map(|best| {
+							view! {
+								<span class="quiz-best">
+									"Best: " {best.score} "/" {best.total}
+									{best.passed(quiz).then_some(" ✓")}
+								</span>
+							}
+						})
+				}}
+			</div>
+		</form>
+	}
+}
+
+/// Answer controls of a question: radio buttons, checkboxes or a text field.
+#[component]
+fn QuestionInput(
+	question: &'static Question,
+	response: Response,
+	name: String,
+	disabled: Signal<bool>,
+) -> impl IntoView {
+	let choice = move |index: usize, label: AnyView, multiple: bool| {
+		let selected = response.selected;
+		view! {
+			<label class="quiz-choice">
+				<input
+					type=if multiple { "checkbox" } else { "radio" }
+					name=name.clone()
+					prop:checked=move || selected.with(|selected| selected.contains(&index))
+					prop:disabled=disabled
+					on:change=move |_| {
+						selected
+							.update(|selected| {
+								if !multiple {
+									*selected = vec![index];
+								} else if let Some(position) = selected.iter().position(|i| *i == index) {
+									selected.remove(position);
+								} else {
+									selected.push(index);
+								}
+							})
+					}
+				/>
+				{label}
+			</label>
+		}
+	};
+
+	match question.answer {
+		Answer::Single(choices) | Answer::Multiple(choices) => {
+			let multiple = matches!(question.answer, Answer::Multiple(_));
+			let choices = choices
+				.iter()
+				.enumerate()
+				.map(|(index, item)| {
+					choice(
+						index,
+						view! { <span inner_html=item.html /> }.into_any(),
+						multiple,
+					)
+				})
+				.collect_view();
+			view! { <div class="quiz-choices">{choices}</div> }.into_any()
+		}
+		Answer::TrueFalse(_) => view! {
+			<div class="quiz-choices">
+				{choice(0, view! { <span>"True"</span> }.into_any(), false)}
+				{choice(1, view! { <span>"False"</span> }.into_any(), false)}
+			</div>
+		}
+		.into_any(),
+		Answer::Short(_) => {
+			let text = response.text;
+			view! {
+				<input
+					type="text"
+					class="quiz-answer"
+					aria-label="Answer"
+					prop:value=text
+					prop:disabled=disabled
+					on:input=move |ev| text.set(event_target_value(&ev))
+				/>
+			}
+			.into_any()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::content::Choice;
+
+	fn question(answer: Answer) -> Question {
+		Question {
+			prompt: "",
+			answer,
+			explanation: None,
+		}
+	}
+
+	const CHOICES: &[Choice] = &[
+		Choice {
+			html: "a",
+			correct: true,
+		},
+		Choice {
+			html: "b",
+			correct: false,
+		},
+		Choice {
+			html: "c",
+			correct: true,
+		},
+	];
+
+	#[test]
+	fn patterns_match_whole_answers() {
+		assert!(matches_pattern("borrow", "Borrow"));
+		assert!(matches_pattern("mutable  borrow", " mutable borrow "));
+		assert!(!matches_pattern("borrow", "borrowing"));
+		assert!(!matches_pattern("borrow", "a borrow"));
+	}
+
+	#[test]
+	fn stars_match_any_text() {
+		assert!(matches_pattern("borrow*", "borrowing"));
+		assert!(matches_pattern("*borrow", "a borrow"));
+		assert!(matches_pattern("a*c*e", "abcde"));
+		assert!(matches_pattern("*", ""));
+		assert!(!matches_pattern("a*c*e", "abcd"));
+		assert!(!matches_pattern("ab*ba", "aba"));
+	}
+
+	#[test]
+	fn choices_must_match_exactly() {
+		let multiple = question(Answer::Multiple(CHOICES));
+		assert!(is_correct(&multiple, &[2, 0], ""));
+		assert!(!is_correct(&multiple, &[0], ""));
+		assert!(!is_correct(&multiple, &[0, 1, 2], ""));
+		let single = question(Answer::Single(&CHOICES[..2]));
+		assert!(is_correct(&single, &[0], ""));
+		assert!(!is_correct(&single, &[1], ""));
+	}
+
+	#[test]
+	fn true_false_answers() {
+		assert!(is_correct(&question(Answer::TrueFalse(true)), &[0], ""));
+		assert!(is_correct(&question(Answer::TrueFalse(false)), &[1], ""));
+		assert!(!is_correct(&question(Answer::TrueFalse(false)), &[], ""));
+	}
+
+	#[test]
+	fn short_answers_need_text() {
+		let short = question(Answer::Short(&["borrow*", "reference"]));
+		assert!(is_correct(&short, &[], "Reference"));
+		assert!(is_correct(&short, &[], "borrowed value"));
+		assert!(!is_correct(&short, &[], "  "));
+		assert!(!is_correct(&short, &[], "a move"));
+	}
+}
@@ -0,0 +1,118 @@
+//! Search box querying the build-time full-text index.
+
+use leptos::ev;
+use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
+
+use crate::search::{search, snippet};
+
+/// Maximum number of results listed under the search box.
+const MAX_RESULTS: usize = 8;
+
+/// Search input with a keyboard-navigable dropdown of matching sections.
+#[component]
+pub fn SearchBox() -> impl IntoView {
+	let (query, set_query) = signal(String::new());
+	let (selected, set_selected) = signal(0usize);
+	let results = Memo::new(move |_| search(&query.get(), MAX_RESULTS));
+	let navigate = use_navigate();
+
+	let open_result = move |index: usize| {
+		if let Some(result) = results.get_untracked().get(index) {
+			navigate(&result.href(), Default::default());
+			set_query.set(String::new());
+		}
+	};
+	let open_on_enter = open_result.clone();
+
+	let on_keydown = move |ev: ev::KeyboardEvent| {
+		let count = results.with_untracked(Vec::len);
+		match ev.key().as_str() {
+			"ArrowDown" if count > 0 => {
+				ev.prevent_default();
+				set_selected.update(|i| *i = (*i + 1) % count);
+			}
+			"ArrowUp" if count > 0 => {
+				ev.prevent_default();
+				set_selected.update(|i| *i = (*i + count - 1) % count);
+			}
+			"Enter" => open_on_enter(selected.get_untracked()),
+			"Escape" => set_query.set(String::new()),
+			_ => {}
+		}
+	};
+
+	view! {
+		<div class="search" role="search">
+			<input
+				type="search"
+				class="search-input"
+				placeholder="Search docs"
+				aria-label="Search docs"
+				prop:value=query
+				on:input=move |ev| {
+					set_query.set(event_target_value(&ev));
+					set_selected.set(0);
+				}
+				on:keydown=on_keydown
+			/>
+			<Show when=move || !query.get().trim().is_empty()>
+				<ul class="search-results">
+					{
+						let open_result = open_result.clone();
+						move || {
+							let query = query.get();
+							let results = results.get();
+							if results.is_empty() {
+								return view! { <li class="search-empty">"No results"</li> }.into_any();
+							}
+							results
+								.into_iter()
+								.enumerate()
+								.map(|(index, result)| {
+									let open_result = open_result.clone();
+									let section = result.section();
+									let page = result.page();
+									view! {
+										<li
+											class="search-result"
+											class:selected=move || selected.get() == index
+											on:mouseenter=move |_| set_selected.set(index)
+										>
+											<a
+												href=result.href()
+												on:click=move |ev| {
+													ev.prevent_default();
+													open_result(index);
+												}
+											>
+												<span class="search-result-title">
+													{page.title}
+													{(!section.anchor.is_empty())
+														.then(|| format!(" › {}", section.heading))}
+												</span>
+												<span class="search-result-snippet">
+													{snippet(section.text, &query)
+														.into_iter()
+														.map(|(text, matched)| {
+															if matched {
+																view! { <mark>{text}</mark> }.into_any()
+															} else {
+																text.into_any()
+															}
+														})
+														.collect_view()}
+												</span>
+											</a>
+										</li>
+									}
+								})
+								.collect_view()
+								.into_any()
+						}
+					}
+				</ul>
+			</Show>
+		</div>
+	}
+}
@@ -1,52 +1,240 @@
 //! Sidebar component for documentation navigation.
 
+use std::collections::HashMap;
+
 use leptos::prelude::*;
 use leptos_router::components::A;
 use leptos_router::hooks::use_location;
+use web_sys::HtmlDetailsElement;
+
+use crate::components::search::SearchBox;
+use crate::components::theme_toggle::ThemeToggle;
+use crate::content::Page;
+use crate::navigation::{get_navigation, Category, NavItem};
+use crate::progress::use_progress;
+use crate::storage;
 
-use crate::navigation::get_navigation;
+/// Storage key of the categories the reader expanded or collapsed.
+const EXPANDED_KEY: &str = "sidebar.expanded";
+
+/// Open state of categories the reader toggled, keyed by [`Category::key`].
+/// Categories missing from the map use their metadata default.
+type Expanded = RwSignal<HashMap<String, bool>>;
 
 /// Sidebar component displaying categorized navigation links.
+///
+/// Categories marked `collapsed` in their metadata start closed. The category
+/// containing the current page opens itself, and states the reader toggles are
+/// remembered across reloads. Completed pages are checked off, and categories show the
+/// share of their pages completed.
 #[component]
 pub fn Sidebar() -> impl IntoView {
-	let location = use_location();
+	let pathname = use_location().pathname;
 	let categories = get_navigation();
 
+	let expanded: Expanded = RwSignal::new(load_expanded());
+	Effect::new(move |_| expanded.with(save_expanded));
+
 	view! {
 		<nav class="sidebar">
 			<div class="sidebar-header">
-				<A href="/" attr:class="sidebar-brand">
-					"Docs"
-				</A>
+				<div class="sidebar-brand-row">
+					<A href="/" attr:class="sidebar-brand">
+						"Docs"
+					</A>
+					<ThemeToggle />
+				</div>
+				<SearchBox />
 			</div>
 			<ul class="sidebar-nav">
 				{categories
 					.into_iter()
 					.map(|category| {
-						view! {
-							<li class="sidebar-category">
-								<span class="category-title">{category.name}</span>
-								<ul class="category-pages">
-									{category
-										.pages
-										.into_iter()
-										.map(|page| {
-											let href = format!("/docs/{}", page.slug);
-											let href_clone = href.clone();
-											let is_active = move || location.pathname.get() == href_clone;
-											view! {
-												<li class:active=is_active>
-													<A href=href>{page.title}</A>
-												</li>
-											}
-										})
-										.collect_view()}
-								</ul>
-							</li>
+						let summary = view! {
+							<summary class="category-title" title=category.description>
+								{category_label(&category)}
+								{category_progress(&category)}
+							</summary>
 						}
+							.into_any();
+						collapsible(category, "sidebar-category", summary, pathname, expanded)
 					})
 					.collect_view()}
 			</ul>
 		</nav>
 	}
 }
+
+/// Renders pages and nested categories, recursing into subfolders.
+fn nav_items(items: Vec<NavItem>, pathname: Memo<String>, expanded: Expanded) -> AnyView {
+	items
+		.into_iter()
+		.map(|item| match item {
+			NavItem::Page(page) => page_link(page, pathname).into_any(),
+			NavItem::Category(category) => {
+				let label = category_label(&category);
+				let progress = category_progress(&category);
+				let summary = match category.index {
+					Some(page) => {
+						let href = page_href(page);
+						let href_clone = href.clone();
+						let is_active = move || pathname.get() == href_clone;
+						view! {
+							<summary
+								class="subcategory-title"
+								class:active=is_active
+								title=category.description
+							>
+								<A href=href>{label}</A>
+								{progress}
+							</summary>
+						}
+						.into_any()
+					}
+					None => view! {
+						<summary class="subcategory-title" title=category.description>
+							{label}
+							{progress}
+						</summary>
+					}
+					.into_any(),
+				};
+				collapsible(category, "sidebar-subcategory", summary, pathname, expanded)
+			}
+		})
+		.collect_view()
+		.into_any()
+}
+
+/// Wraps a category's pages in a `<details>` whose open state is kept in `expanded`.
+/// Navigating to a page inside the category opens it without recording that in
+/// `expanded`, so only the reader's own toggles are remembered.
+fn collapsible(
+	category: Category,
+	class: &'static str,
+	summary: AnyView,
+	pathname: Memo<String>,
+	expanded: Expanded,
+) -> AnyView {
+	let default_open = !category.collapsed;
+	let key = StoredValue::new(category.key.clone());
+	// Whether navigation opened the category since the reader last toggled it.
+	let auto_open = RwSignal::new(false);
+	let is_open = move || {
+		auto_open.get()
+			|| expanded
+				.with(|states| key.with_value(|key| states.get(key).copied()))
+				.unwrap_or(default_open)
+	};
+
+	let slugs: Vec<&'static str> = category.pages().iter().map(|page| page.slug).collect();
+	// Tracks only the location, so closing the current page's category keeps it closed.
+	Effect::new(move |_| {
+		let path = pathname.get();
+		let current = path
+			.strip_prefix("/docs/")
+			.is_some_and(|slug| slugs.contains(&slug));
+		if current {
+			auto_open.set(true);
+		}
+	});
+
+	let on_toggle = move |ev: leptos::ev::Event| {
+		let open = event_target::<HtmlDetailsElement>(&ev).open();
+		// `toggle` also fires when the state is applied from the signals; only
+		// record changes so untouched categories keep following their metadata.
+		if untrack(is_open) != open {
+			auto_open.set(false);
+			expanded.update(|states| {
+				states.insert(key.get_value(), open);
+			});
+		}
+	};
+
+	view! {
+		<li class=class>
+			<details prop:open=is_open on:toggle=on_toggle>
+				{summary}
+				<ul class="category-pages">{nav_items(category.items, pathname, expanded)}</ul>
+			</details>
+		</li>
+	}
+	.into_any()
+}
+
+/// Reads the stored open states, one `open <key>` or `closed <key>` line each.
+fn load_expanded() -> HashMap<String, bool> {
+	storage::load(EXPANDED_KEY)
+		.unwrap_or_default()
+		.lines()
+		.filter_map(|line| match line.split_once(' ')? {
+			("open", key) => Some((key.to_string(), true)),
+			("closed", key) => Some((key.to_string(), false)),
+			_ => None,
+		})
+		.collect()
+}
+
+fn save_expanded(states: &HashMap<String, bool>) {
+	let text = states
+		.iter()
+		.map(|(key, open)| format!("{} {key}", if *open { "open" } else { "closed" }))
+		.collect::<Vec<_>>()
+		.join("\n");
+	storage::save(EXPANDED_KEY, &text);
+}
+
+/// Category name, preceded by its icon if it has one.
+fn category_label(category: &Category) -> impl IntoView + use<> {
+	let name = category.name;
+	let icon = category
+		.icon
+		.map(|icon| view! { <span class="category-icon" aria-hidden="true">{icon}</span> });
+	view! { {icon} {name} }
+}
+
+/// Share of the category's pages the learner completed, hidden until they start one.
+fn category_progress(category: &Category) -> impl IntoView + use<> {
+	let progress = use_progress();
+	let pages = category.pages();
+	move || {
+		let percent = progress.with(|progress| progress.percent_complete(&pages));
+		(percent > 0).then(|| {
+			view! {
+				<span class="category-progress" title="Pages completed">
+					{percent}
+					"%"
+				</span>
+			}
+		})
+	}
+}
+
+fn page_href(page: &Page) -> String {
+	format!("/docs/{}", page.slug)
+}
+
+fn page_link(page: &'static Page, pathname: Memo<String>) -> impl IntoView {
+	let href = page_href(page);
+	let href_clone = href.clone();
+	let is_active = move || pathname.get() == href_clone;
+	let progress = use_progress();
+	let complete = move || progress.with(|progress| progress.is_complete(page));
+	view! {
+		<li class:active=is_active class:completed=complete>
+			<A href=href>
+				{page.title}
+				{move || {
+					complete()
+						.then(|| {
+							view! {
+								<span class="progress-check" aria-label="Completed">
+									"✓"
+								</span>
+							}
+						})
+				}}
+			</A>
+		</li>
+	}
+}
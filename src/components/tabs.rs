@@ -0,0 +1,141 @@
+//! Behaviour of the tab groups the build renders from `::: tabs` divs.
+//!
+//! Choosing a tab selects the tab with the same key in every group sharing its `group`, and
+//! the choice is remembered per group for later pages and visits. Like the code block
+//! toolbars, the handlers are delegated from the page's article.
+
+use leptos::ev::{KeyboardEvent, MouseEvent};
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+use crate::storage;
+
+/// Storage key of the tab chosen for `group`.
+fn storage_key(group: &str) -> String {
+	format!("tabs.{group}")
+}
+
+fn elements(container: &Element, selector: &str) -> Vec<Element> {
+	let Ok(nodes) = container.query_selector_all(selector) else {
+		return Vec::new();
+	};
+	(0..nodes.length())
+		.filter_map(|index| nodes.item(index)?.dyn_into::<Element>().ok())
+		.collect()
+}
+
+/// Tab buttons of the tab group `tabs`, in order.
+fn tabs_of(tabs: &Element) -> Vec<Element> {
+	elements(tabs, ":scope > .tab-list > [role=\"tab\"]")
+}
+
+/// Shows the panel of `tab` and hides its siblings'.
+fn select(tab: &Element) {
+	let Some(tabs) = tab.closest(".tabs").ok().flatten() else {
+		return;
+	};
+	for other in tabs_of(&tabs) {
+		let selected = other == *tab;
+		let _ = other.set_attribute("aria-selected", &selected.to_string());
+		let _ = other.set_attribute("tabindex", if selected { "0" } else { "-1" });
+		let panel = other
+			.get_attribute("aria-controls")
+			.and_then(|id| document().get_element_by_id(&id));
+		if let Some(panel) = panel {
+			let _ = if selected {
+				panel.remove_attribute("hidden")
+			} else {
+				panel.set_attribute("hidden", "")
+			};
+		}
+	}
+}
+
+/// Selects the tab keyed `key` in every tab group of `group` under `container`.
+fn select_in_group(container: &Element, group: &str, key: &str) {
+	for tabs in elements(container, ".tabs[data-group]") {
+		if tabs.get_attribute("data-group").as_deref() != Some(group) {
+			continue;
+		}
+		let tab = tabs_of(&tabs)
+			.into_iter()
+			.find(|tab| tab.get_attribute("data-tab").as_deref() == Some(key));
+		if let Some(tab) = tab {
+			select(&tab);
+		}
+	}
+}
+
+/// Selects `tab`, syncing and remembering the choice if its group has a `group` key.
+fn choose(tab: &Element) {
+	let tabs = tab.closest(".tabs").ok().flatten();
+	let group = tabs.and_then(|tabs| tabs.get_attribute("data-group"));
+	match (group, tab.get_attribute("data-tab")) {
+		(Some(group), Some(key)) => {
+			if let Some(container) = document().document_element() {
+				select_in_group(&container, &group, &key);
+			}
+			storage::save(&storage_key(&group), &key);
+		}
+		_ => select(tab),
+	}
+}
+
+/// Applies the remembered choices to the tab groups under `container`.
+pub fn restore_tabs(container: &Element) {
+	for tabs in elements(container, ".tabs[data-group]") {
+		let Some(group) = tabs.get_attribute("data-group") else {
+			continue;
+		};
+		if let Some(key) = storage::load(&storage_key(&group)) {
+			select_in_group(&tabs, &group, &key);
+		}
+	}
+}
+
+fn target_tab(target: Option<web_sys::EventTarget>) -> Option<Element> {
+	target?
+		.dyn_into::<Element>()
+		.ok()?
+		.closest(".tabs [role=\"tab\"]")
+		.ok()?
+}
+
+/// Selects a tab when it is clicked.
+pub fn on_tab_click(ev: &MouseEvent) {
+	if let Some(tab) = target_tab(ev.target()) {
+		choose(&tab);
+	}
+}
+
+/// Moves between the tabs of a group with the arrow, Home and End keys.
+///
+/// Handled keys prevent their default action, so page-level shortcuts such as the
+/// previous/next page arrows leave them alone.
+pub fn on_tab_keydown(ev: &KeyboardEvent) {
+	let Some(tab) = target_tab(ev.target()) else {
+		return;
+	};
+	let Some(tabs) = tab.closest(".tabs").ok().flatten() else {
+		return;
+	};
+	let siblings = tabs_of(&tabs);
+	let Some(index) = siblings.iter().position(|other| *other == tab) else {
+		return;
+	};
+	let count = siblings.len();
+	let next = match ev.key().as_str() {
+		"ArrowRight" => (index + 1) % count,
+		"ArrowLeft" => (index + count - 1) % count,
+		"Home" => 0,
+		"End" => count - 1,
+		_ => return,
+	};
+	ev.prevent_default();
+	let next = &siblings[next];
+	choose(next);
+	if let Some(next) = next.dyn_ref::<HtmlElement>() {
+		let _ = next.focus();
+	}
+}
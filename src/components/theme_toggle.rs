@@ -0,0 +1,39 @@
+//! Button switching between the light, dark and system themes.
+
+use leptos::prelude::*;
+
+use crate::theme::{use_theme, ThemeChoice};
+
+fn icon(choice: ThemeChoice) -> &'static str {
+	match choice {
+		ThemeChoice::Light => "☀",
+		ThemeChoice::Dark => "☾",
+		ThemeChoice::System => "◐",
+	}
+}
+
+/// Cycles the theme light → dark → system, showing the current choice.
+#[component]
+pub fn ThemeToggle() -> impl IntoView {
+	let theme = use_theme();
+	let label = move || {
+		let choice = theme.choice();
+		let current = match choice {
+			ThemeChoice::System => format!("System ({})", theme.theme().as_str()),
+			_ => choice.label().to_string(),
+		};
+		format!("Theme: {current}. Switch to {}", choice.next().label())
+	};
+
+	view! {
+		<button
+			class="theme-toggle"
+			type="button"
+			title=label
+			aria-label=label
+			on:click=move |_| theme.cycle()
+		>
+			<span aria-hidden="true">{move || icon(theme.choice())}</span>
+		</button>
+	}
+}
@@ -0,0 +1,107 @@
+//! "On this page" panel listing the current page's headings.
+
+use std::cell::Cell;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+use leptos::ev;
+use leptos::prelude::*;
+use web_sys::{ScrollBehavior, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+use crate::content::TocEntry;
+
+/// Heading levels listed in the panel.
+const TOC_LEVELS: RangeInclusive<u8> = 2..=3;
+
+/// Distance from the top of the viewport at which a heading becomes the current section.
+const ACTIVE_OFFSET: f64 = 96.0;
+
+/// Id of the listed heading whose section is in view: the last one scrolled past the offset.
+pub fn heading_in_view(entries: &[TocEntry]) -> Option<&'static str> {
+	let document = document();
+	entries
+		.iter()
+		.filter(|entry| TOC_LEVELS.contains(&entry.level))
+		.take_while(|entry| {
+			document
+				.get_element_by_id(entry.id)
+				.is_some_and(|heading| heading.get_bounding_client_rect().top() <= ACTIVE_OFFSET)
+		})
+		.last()
+		.map(|entry| entry.id)
+}
+
+/// Table of contents that highlights the section in view and scrolls smoothly on click.
+#[component]
+pub fn TableOfContents(#[prop(into)] entries: Signal<&'static [TocEntry]>) -> impl IntoView {
+	let (active, set_active) = signal(None::<&'static str>);
+	let listed = move || {
+		entries
+			.get()
+			.iter()
+			.filter(|entry| TOC_LEVELS.contains(&entry.level))
+			.collect::<Vec<_>>()
+	};
+
+	// Scroll-spy: follow the section in view as the page scrolls.
+	let update_active = move || set_active.set(heading_in_view(entries.get_untracked()));
+
+	// Recompute once the new page's content has been mounted.
+	Effect::new(move |_| {
+		entries.track();
+		request_animation_frame(update_active);
+	});
+	// Scroll events can fire many times a frame; measure the headings at most once per frame.
+	let frame_pending = Rc::new(Cell::new(false));
+	let handle = window_event_listener(ev::scroll, move |_| {
+		if !frame_pending.replace(true) {
+			let frame_pending = Rc::clone(&frame_pending);
+			request_animation_frame(move || {
+				frame_pending.set(false);
+				update_active();
+			});
+		}
+	});
+	on_cleanup(move || handle.remove());
+
+	view! {
+		<Show when=move || !listed().is_empty()>
+			<nav class="toc" aria-label="On this page">
+				<p class="toc-title">"On this page"</p>
+				<ul>
+					{move || {
+						listed()
+							.into_iter()
+							.map(|entry| {
+								let id = entry.id;
+								view! {
+									<li
+										class=format!("toc-level-{}", entry.level)
+										class:active=move || active.get() == Some(id)
+									>
+										<a
+											href=format!("#{id}")
+											on:click=move |ev| {
+												// Scroll ourselves so the router never sees the fragment link.
+												ev.prevent_default();
+												if let Some(heading) = document().get_element_by_id(id) {
+													let options = ScrollIntoViewOptions::new();
+													options.set_behavior(ScrollBehavior::Smooth);
+													options.set_block(ScrollLogicalPosition::Start);
+													heading.scroll_into_view_with_scroll_into_view_options(&options);
+													set_active.set(Some(id));
+												}
+											}
+										>
+											{entry.text}
+										</a>
+									</li>
+								}
+							})
+							.collect_view()
+					}}
+				</ul>
+			</nav>
+		</Show>
+	}
+}
@@ -1 +1,25 @@
 include!(concat!(env!("OUT_DIR"), "/content.rs"));
+
+/// Finds the page for a slug, falling back to pages listing it in `aliases`.
+pub fn find_page(slug: &str) -> Option<&'static Page> {
+	PAGES
+		.iter()
+		.find(|page| page.slug == slug)
+		.or_else(|| PAGES.iter().find(|page| page.aliases.contains(&slug)))
+}
+
+/// Display settings of the top-level category `name`, from `[categories]` in bezel.toml.
+pub fn category_meta(name: &str) -> Option<&'static CategoryMeta> {
+	CATEGORIES
+		.iter()
+		.find(|(key, _)| *key == name)
+		.map(|(_, meta)| meta)
+}
+
+/// Display settings of the folder category at `slug`, from its `_category.yml`.
+pub fn folder_meta(slug: &str) -> Option<&'static CategoryMeta> {
+	FOLDER_CATEGORIES
+		.iter()
+		.find(|(key, _)| *key == slug)
+		.map(|(_, meta)| meta)
+}
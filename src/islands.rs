@@ -0,0 +1,99 @@
+//! Component islands: Leptos components embedded in Markdown content.
+//!
+//! The build turns `::: {.component name="Button" increment=5}` divs (and
+//! `[]{.component name="..."}` spans) into placeholders carrying the component name and its
+//! props as JSON. Components are registered by name on an [`Islands`] registry provided by
+//! the root component, and [`mount_islands`] mounts them once a page's content is in the DOM.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use leptos::mount::{mount_to, UnmountHandle};
+use leptos::prelude::*;
+use leptos::tachys::view::any_view::AnyViewState;
+use leptos::wasm_bindgen::JsCast;
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use web_sys::{Element, HtmlElement};
+
+/// Props of an island, from the attributes of its div or span other than `name`.
+///
+/// Numbers and `true`/`false` keep their type; other values are strings.
+#[derive(Clone, Debug, Default)]
+pub struct Props(Map<String, Value>);
+
+impl Props {
+	/// Parses the `data-props` JSON of a placeholder; invalid JSON gives no props.
+	fn parse(json: &str) -> Self {
+		Props(serde_json::from_str(json).unwrap_or_default())
+	}
+
+	/// The prop `key` converted to `T`, if present and of a matching type.
+	pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+		serde_json::from_value(self.0.get(key)?.clone()).ok()
+	}
+}
+
+type Render = Arc<dyn Fn(&Props) -> AnyView + Send + Sync>;
+
+/// Components that pages can embed, by the name used in Markdown.
+#[derive(Clone, Default)]
+pub struct Islands(HashMap<&'static str, Render>);
+
+impl Islands {
+	/// Registers `render` as the component called `name`, replacing any earlier one.
+	pub fn register<V>(
+		mut self,
+		name: &'static str,
+		render: impl Fn(&Props) -> V + Send + Sync + 'static,
+	) -> Self
+	where
+		V: IntoView + 'static,
+	{
+		self.0
+			.insert(name, Arc::new(move |props| render(props).into_any()));
+		self
+	}
+}
+
+/// Makes `islands` available to [`mount_islands`]. Call once, from the root component.
+pub fn provide_islands(islands: Islands) {
+	provide_context(islands);
+}
+
+/// A component mounted into a placeholder; dropping it unmounts the component.
+pub type MountedIsland = UnmountHandle<AnyViewState>;
+
+/// Mounts the registered component into every island placeholder under `container`.
+///
+/// Components are owned by the current reactive owner, so they can use its context. A
+/// placeholder naming an unregistered component keeps its fallback content.
+pub fn mount_islands(container: &Element) -> Vec<MountedIsland> {
+	let Some(islands) = use_context::<Islands>() else {
+		return Vec::new();
+	};
+	let Ok(placeholders) = container.query_selector_all(".component-island[data-component]") else {
+		return Vec::new();
+	};
+	let mut mounted = Vec::new();
+	for index in 0..placeholders.length() {
+		let Some(placeholder) = placeholders
+			.item(index)
+			.and_then(|node| node.dyn_into::<HtmlElement>().ok())
+		else {
+			continue;
+		};
+		let name = placeholder
+			.get_attribute("data-component")
+			.unwrap_or_default();
+		let Some(render) = islands.0.get(name.as_str()).cloned() else {
+			warn!("no component registered for island `{name}`");
+			continue;
+		};
+		let props = Props::parse(&placeholder.get_attribute("data-props").unwrap_or_default());
+		placeholder.set_inner_html("");
+		mounted.push(mount_to(placeholder, move || render(&props)));
+	}
+	mounted
+}
@@ -1,8 +1,5 @@
 //! Leptos client-side app wiring and routes.
 
-// web_sys is used by the binary, not the library
-use web_sys as _;
-
 use leptos::prelude::*;
 use leptos_meta::*;
 use leptos_router::components::*;
@@ -11,15 +8,30 @@ use log::{info, Level};
 use {console_error_panic_hook, console_log};
 
 // Modules
+mod clipboard;
 mod components;
 mod content;
+mod islands;
 mod navigation;
 mod pages;
+mod progress;
+mod search;
+mod site;
+mod storage;
+mod theme;
 
+// App-wide components
+use crate::components::command_palette::CommandPalette;
+use crate::components::counter_btn::Button;
+use crate::components::quiz::Quiz;
+use crate::islands::{provide_islands, Islands};
 // Top-Level pages
 use crate::pages::home::Home;
 use crate::pages::markdown::MarkdownPage;
 use crate::pages::not_found::NotFound;
+use crate::progress::provide_progress;
+use crate::site::{document_title, HIGHLIGHT_CSS, SITE_NAME};
+use crate::theme::provide_theme;
 
 /// Initialize logging and panic hooks for the WASM target.
 pub fn init_logging() {
@@ -33,21 +45,45 @@ pub fn init_logging() {
 pub fn App() -> impl IntoView {
 	// Provides context that manages stylesheets, titles, meta tags, etc.
 	provide_meta_context();
+	// Applies the saved light/dark choice and shares it with components.
+	provide_theme();
+	// Records pages read and quizzes passed for the sidebar and the home dashboard.
+	provide_progress();
+	// Components that Markdown pages can embed with `::: {.component name="..."}`.
+	provide_islands(
+		Islands::default()
+			.register("Button", |props| {
+				view! { <Button increment=props.get("increment").unwrap_or(1) /> }
+			})
+			// Placeholders of `.quiz` divs, emitted by the build.
+			.register("Quiz", |props| {
+				view! {
+					<Quiz
+						page=props.get("page").unwrap_or_default()
+						quiz=props.get("quiz").unwrap_or_default()
+					/>
+				}
+			}),
+	);
 
 	view! {
-		<Html attr:lang="en" attr:dir="ltr" attr:data-theme="light" />
+		<Html attr:lang="en" attr:dir="ltr" />
 
-		// sets the document title
-		<Title text="Welcome to Leptos CSR" />
+		// sets the document title; routed pages replace the text with their own title
+		<Title formatter=document_title text=SITE_NAME />
 
 		// injects metadata in the <head> of the page
 		<Meta charset="UTF-8" />
 		<Meta name="viewport" content="width=device-width, initial-scale=1.0" />
 
+		// colours of build-time highlighted code blocks
+		<Style>{HIGHLIGHT_CSS}</Style>
+
 		<Router>
+			<CommandPalette />
 			<Routes fallback=|| view! { <NotFound /> }>
 				<Route path=path!("/") view=Home />
-				<Route path=path!("/docs/:slug") view=MarkdownPage />
+				<Route path=path!("/docs/*slug") view=MarkdownPage />
 			</Routes>
 		</Router>
 	}
@@ -2,27 +2,215 @@
 
 use std::collections::BTreeMap;
 
-use crate::content::{Page, PAGES};
+use crate::content::{category_meta, find_page, folder_meta, CategoryMeta, Page, PAGES};
+use crate::site::{CATEGORY_ORDER, DEFAULT_CATEGORY};
 
-/// A category containing ordered pages.
+/// An entry in a category: either a page or a nested category.
+pub enum NavItem {
+	Page(&'static Page),
+	Category(Category),
+}
+
+impl NavItem {
+	fn order(&self) -> u32 {
+		match self {
+			NavItem::Page(page) => page.order,
+			NavItem::Category(category) => category.order,
+		}
+	}
+
+	fn category(&self) -> &'static str {
+		match self {
+			NavItem::Page(page) => page.category,
+			NavItem::Category(category) => category.category(),
+		}
+	}
+}
+
+/// A category containing ordered pages and nested categories.
+///
+/// Top-level categories come from frontmatter; nested categories mirror
+/// module folders that contain sub-pages or further modules. Both can be
+/// described by category metadata from bezel.toml or `_category.yml`.
 pub struct Category {
+	/// Stable identifier that survives renames through category metadata.
+	pub key: String,
 	pub name: &'static str,
-	pub pages: Vec<&'static Page>,
+	/// The folder's own `index.md` page, if it has one.
+	pub index: Option<&'static Page>,
+	pub order: u32,
+	pub description: Option<&'static str>,
+	pub icon: Option<&'static str>,
+	/// Whether the category starts collapsed in the sidebar.
+	pub collapsed: bool,
+	pub items: Vec<NavItem>,
+}
+
+impl Category {
+	/// Builds a category, letting `meta` override the default name and order.
+	fn new(
+		key: String,
+		name: &'static str,
+		index: Option<&'static Page>,
+		order: u32,
+		meta: Option<&'static CategoryMeta>,
+		items: Vec<NavItem>,
+	) -> Self {
+		Category {
+			key,
+			name: meta.and_then(|meta| meta.name).unwrap_or(name),
+			index,
+			order: meta.and_then(|meta| meta.order).unwrap_or(order),
+			description: meta.and_then(|meta| meta.description),
+			icon: meta.and_then(|meta| meta.icon),
+			collapsed: meta.is_some_and(|meta| meta.collapsed),
+			items,
+		}
+	}
+
+	/// Every page shown inside the category, including nested ones, in sidebar order.
+	pub fn pages(&self) -> Vec<&'static Page> {
+		let mut pages: Vec<&'static Page> = self.index.into_iter().collect();
+		for item in &self.items {
+			match item {
+				NavItem::Page(page) => pages.push(page),
+				NavItem::Category(category) => pages.extend(category.pages()),
+			}
+		}
+		pages
+	}
+
+	/// Frontmatter category of the folder, taken from its index or first page.
+	fn category(&self) -> &'static str {
+		self.index
+			.map(|page| page.category)
+			.or_else(|| self.items.first().map(NavItem::category))
+			.unwrap_or(DEFAULT_CATEGORY)
+	}
 }
 
-/// Returns pages grouped by category, sorted by order within each category.
+/// Returns the module tree grouped by frontmatter category, sorted by order.
+///
+/// Top-level categories without an explicit `order` take their position in
+/// `category_order` as order; the remaining ones follow alphabetically.
 pub fn get_navigation() -> Vec<Category> {
-	let mut categories: BTreeMap<&'static str, Vec<&'static Page>> = BTreeMap::new();
+	let mut categories: BTreeMap<&'static str, Vec<NavItem>> = BTreeMap::new();
 
-	for page in PAGES.iter() {
-		categories.entry(page.category).or_default().push(page);
+	for item in child_items("") {
+		categories.entry(item.category()).or_default().push(item);
 	}
 
+	let mut categories: Vec<Category> = categories
+		.into_iter()
+		.map(|(name, items)| {
+			let order = CATEGORY_ORDER
+				.iter()
+				.position(|configured| *configured == name)
+				.map_or(u32::MAX, |position| position as u32);
+			Category::new(
+				format!("category:{name}"),
+				name,
+				None,
+				order,
+				category_meta(name),
+				items,
+			)
+		})
+		.collect();
+	categories.sort_by_key(|category| category.order);
 	categories
+}
+
+/// Returns every page in sidebar order, folder index pages before their contents.
+pub fn reading_order() -> Vec<&'static Page> {
+	fn push_items(items: Vec<NavItem>, pages: &mut Vec<&'static Page>) {
+		for item in items {
+			match item {
+				NavItem::Page(page) => pages.push(page),
+				NavItem::Category(category) => {
+					pages.extend(category.index);
+					push_items(category.items, pages);
+				}
+			}
+		}
+	}
+
+	let mut pages = Vec::new();
+	for category in get_navigation() {
+		push_items(category.items, &mut pages);
+	}
+	pages
+}
+
+/// Returns the pages before and after `page` in reading order, crossing category
+/// boundaries. `prev`/`next` frontmatter overrides take precedence.
+pub fn neighbours(page: &Page) -> (Option<&'static Page>, Option<&'static Page>) {
+	let order = reading_order();
+	let index = order.iter().position(|other| other.slug == page.slug);
+	let at = |offset: isize| {
+		let index = index?.checked_add_signed(offset)?;
+		order.get(index).copied()
+	};
+	(
+		page.prev.map_or_else(|| at(-1), find_page),
+		page.next.map_or_else(|| at(1), find_page),
+	)
+}
+
+/// Builds the ordered items directly inside the module folder `dir`.
+fn child_items(dir: &'static str) -> Vec<NavItem> {
+	let mut paths: Vec<&'static str> = PAGES
+		.iter()
+		.filter_map(|page| child_path(dir, page.slug))
+		.collect();
+	paths.sort_unstable();
+	paths.dedup();
+
+	let mut items: Vec<NavItem> = paths
 		.into_iter()
-		.map(|(name, mut pages)| {
-			pages.sort_by_key(|p| p.order);
-			Category { name, pages }
+		.map(|path| {
+			let index = PAGES.iter().find(|page| page.slug == path);
+			if has_descendants(path) {
+				let items = child_items(path);
+				NavItem::Category(Category::new(
+					format!("folder:{path}"),
+					index.map_or_else(|| path.rsplit('/').next().unwrap_or(path), |p| p.title),
+					index,
+					index
+						.map(|page| page.order)
+						.or_else(|| items.iter().map(NavItem::order).min())
+						.unwrap_or(u32::MAX),
+					folder_meta(path),
+					items,
+				))
+			} else {
+				NavItem::Page(index.expect("leaf module path is a page slug"))
+			}
 		})
-		.collect()
+		.collect();
+	items.sort_by_key(NavItem::order);
+	items
+}
+
+/// Returns the path of the immediate child of `dir` that contains `slug`.
+fn child_path(dir: &str, slug: &'static str) -> Option<&'static str> {
+	let rest = if dir.is_empty() {
+		slug
+	} else {
+		slug.strip_prefix(dir)?.strip_prefix('/')?
+	};
+	if rest.is_empty() {
+		return None;
+	}
+	let end = slug.len() - rest.len() + rest.find('/').unwrap_or(rest.len());
+	Some(&slug[..end])
+}
+
+/// Whether any page is nested below `path`, as a sub-page or in a subfolder.
+fn has_descendants(path: &str) -> bool {
+	PAGES.iter().any(|page| {
+		page.slug
+			.strip_prefix(path)
+			.is_some_and(|rest| rest.starts_with('/'))
+	})
 }
@@ -2,6 +2,7 @@ use leptos::prelude::*;
 use leptos_router::components::A;
 
 use crate::components::counter_btn::Button;
+use crate::components::progress_dashboard::ProgressDashboard;
 use crate::content::PAGES;
 
 /// Default Home Page
@@ -49,6 +50,8 @@ pub fn Home() -> impl IntoView {
 					<Button increment=5 />
 				</div>
 
+				<ProgressDashboard />
+
 				<section class="docs">
 					<h2>"Generated Markdown Pages"</h2>
 					<ul class="doc-list">
@@ -61,6 +64,9 @@ pub fn Home() -> impl IntoView {
 											<A href=format!("/docs/{}", page.slug)>
 												{page.slug}
 											</A>
+											{page
+												.description
+												.map(|description| view! { <p class="doc-description">{description}</p> })}
 										</li>
 									}
 								})
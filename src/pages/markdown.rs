@@ -1,25 +1,225 @@
 //! Markdown page component with docs layout.
 
+use std::cell::Cell;
+use std::rc::Rc;
+
+use leptos::ev::{self, MouseEvent};
+use leptos::html;
 use leptos::prelude::*;
-use leptos_router::hooks::use_params_map;
+use leptos_meta::{Link, Meta, Title};
+use leptos_router::components::Redirect;
+use leptos_router::hooks::{use_location, use_navigate, use_params_map};
+use leptos_router::NavigateOptions;
+use web_sys::ScrollBehavior;
 
+use crate::components::code_blocks::{enhance_code_blocks, on_code_block_click};
+use crate::components::content_links::{content_link, scroll_to_fragment, ContentLink};
 use crate::components::docs_layout::DocsLayout;
-use crate::content::PAGES;
+use crate::components::page_nav::PageNav;
+use crate::components::tabs::{on_tab_click, on_tab_keydown, restore_tabs};
+use crate::content::{find_page, Page};
+use crate::islands::mount_islands;
+use crate::progress::{scroll_depth, use_progress};
+use crate::site::{page_url, SITE_NAME};
 
 #[component]
 pub fn MarkdownPage() -> impl IntoView {
 	let params = use_params_map();
-	let html = move || {
+	let slug = move || {
 		params
 			.with(|p| p.get("slug").map(|s| s.to_string()))
-			.and_then(|slug| PAGES.iter().find(|page| page.slug == slug))
-			.map(|page| page.html)
-			.unwrap_or("<p>Not found</p>")
+			.unwrap_or_default()
+	};
+	let page = move || find_page(&slug());
+	let html = move || page().map(|page| page.html).unwrap_or("<p>Not found</p>");
+	let toc = Signal::derive(move || page().map_or(&[][..], |page| page.toc));
+	let nav_page = Signal::derive(page);
+	let title = Signal::derive(move || page().map(|page| page.title));
+	let article = NodeRef::<html::Article>::new();
+
+	// Add code block toolbars, restore chosen tabs and mount component islands once each
+	// page's content is in the DOM. Islands of the previous content are unmounted first.
+	let owner = Owner::current().expect("MarkdownPage has an owner");
+	let islands = StoredValue::new_local(Vec::new());
+	Effect::new(move |_| {
+		html();
+		let owner = owner.clone();
+		request_animation_frame(move || {
+			if let Some(article) = article.get_untracked() {
+				enhance_code_blocks(&article);
+				restore_tabs(&article);
+				islands.update_value(|islands| {
+					islands.clear();
+					islands.extend(owner.with(|| mount_islands(&article)));
+				});
+			}
+		});
+	});
+
+	// Record the visit, and how far down the page the learner reads.
+	let progress = use_progress();
+	let record_depth = move || {
+		if let Some(page) = untrack(page) {
+			progress.record_depth(page, scroll_depth());
+		}
+	};
+	Effect::new(move |_| {
+		if let Some(page) = page() {
+			progress.visit(page);
+			request_animation_frame(record_depth);
+		}
+	});
+	// Scroll events can fire many times a frame; measure the page at most once per frame.
+	let frame_pending = Rc::new(Cell::new(false));
+	let handle = window_event_listener(ev::scroll, move |_| {
+		if !frame_pending.replace(true) {
+			let frame_pending = Rc::clone(&frame_pending);
+			request_animation_frame(move || {
+				frame_pending.set(false);
+				record_depth();
+			});
+		}
+	});
+	on_cleanup(move || handle.remove());
+
+	// Scroll to the URL's fragment once the content is in place: on first load, on arriving
+	// from another page and when only the fragment changes.
+	let location = use_location();
+	Effect::new(move |previous_path: Option<String>| {
+		let hash = location.hash.get();
+		let path = location.pathname.get();
+		html();
+		let behavior = if previous_path.as_ref() == Some(&path) {
+			ScrollBehavior::Smooth
+		} else {
+			ScrollBehavior::Auto
+		};
+		request_animation_frame(move || {
+			if let Some(id) = hash.strip_prefix('#').filter(|id| !id.is_empty()) {
+				scroll_to_fragment(id, behavior);
+			}
+		});
+		path
+	});
+
+	// Links in the content go through the router (see `content_links`); following the
+	// fragment already in the URL just scrolls to it again.
+	let navigate = use_navigate();
+	let on_click = move |ev: MouseEvent| {
+		on_code_block_click(&ev);
+		on_tab_click(&ev);
+		match content_link(&ev) {
+			Some(ContentLink::Fragment(id)) => {
+				ev.prevent_default();
+				if location.hash.get_untracked() == format!("#{id}") {
+					scroll_to_fragment(&id, ScrollBehavior::Smooth);
+				} else {
+					let path = location.pathname.get_untracked();
+					let options = NavigateOptions {
+						scroll: false,
+						..Default::default()
+					};
+					navigate(&format!("{path}#{id}"), options);
+				}
+			}
+			Some(ContentLink::Route(route)) => {
+				ev.prevent_default();
+				navigate(&route, Default::default());
+			}
+			None => {}
+		}
 	};
 
 	view! {
-		<DocsLayout>
-			<article class="markdown-body" inner_html=move || html().to_string() />
+		<DocsLayout title toc>
+			// Aliases resolve to their page; send the browser to the canonical slug.
+			{move || {
+				page()
+					.filter(|page| page.slug != slug())
+					.map(|page| view! { <Redirect path=format!("/docs/{}", page.slug) /> })
+			}}
+			{move || match page() {
+				Some(page) => view! { <PageHead page /> }.into_any(),
+				None => view! { <Title text="Page not found" /> }.into_any(),
+			}}
+			{move || page().map(|page| view! { <PageMeta page /> })}
+			<article
+				class="markdown-body"
+				node_ref=article
+				inner_html=move || html().to_string()
+				on:click=on_click
+				on:keydown=move |ev| on_tab_keydown(&ev)
+			/>
+			<PageNav page=nav_page />
 		</DocsLayout>
 	}
 }
+
+/// Document title, description, Open Graph and canonical link tags for a page.
+#[component]
+fn PageHead(page: &'static Page) -> impl IntoView {
+	view! {
+		<Title text=page.title />
+		<Meta property="og:title" content=page.title />
+		<Meta property="og:type" content="article" />
+		<Meta property="og:site_name" content=SITE_NAME />
+		{page
+			.description
+			.map(|description| {
+				view! {
+					<Meta name="description" content=description />
+					<Meta property="og:description" content=description />
+				}
+			})}
+		{page.date.map(|date| view! { <Meta property="article:published_time" content=date /> })}
+		{page.updated.map(|updated| view! { <Meta property="article:modified_time" content=updated /> })}
+		{page.tags.iter().map(|tag| view! { <Meta property="article:tag" content=*tag /> }).collect_view()}
+		{page_url(page.slug)
+			.map(|url| {
+				view! {
+					<Meta property="og:url" content=url.clone() />
+					<Link rel="canonical" href=url />
+				}
+			})}
+	}
+}
+
+/// Draft badge, byline and tags from frontmatter, shown above the page content.
+#[component]
+fn PageMeta(page: &'static Page) -> impl IntoView {
+	let has_meta = page.draft
+		|| !page.authors.is_empty()
+		|| page.date.is_some()
+		|| page.updated.is_some()
+		|| !page.tags.is_empty();
+
+	has_meta.then(|| {
+		view! {
+			<header class="page-meta">
+				{page.draft.then(|| view! { <span class="draft-badge">"Draft"</span> })}
+				{(!page.authors.is_empty())
+					.then(|| {
+						view! { <span class="page-authors">"By " {page.authors.join(", ")}</span> }
+					})}
+				{page.date.map(|date| view! { <time class="page-date" datetime=date>{date}</time> })}
+				{page
+					.updated
+					.map(|updated| {
+						view! {
+							<span class="page-updated">
+								"Updated " <time datetime=updated>{updated}</time>
+							</span>
+						}
+					})}
+				{(!page.tags.is_empty())
+					.then(|| {
+						view! {
+							<ul class="page-tags">
+								{page.tags.iter().map(|tag| view! { <li>{*tag}</li> }).collect_view()}
+							</ul>
+						}
+					})}
+			</header>
+		}
+	})
+}
@@ -1,7 +1,11 @@
 use leptos::prelude::*;
+use leptos_meta::Title;
 
 /// 404 Not Found Page
 #[component]
 pub fn NotFound() -> impl IntoView {
-	view! { <h1>"Uh oh!" <br /> "We couldn't find that page!"</h1> }
+	view! {
+		<Title text="Page not found" />
+		<h1>"Uh oh!" <br /> "We couldn't find that page!"</h1>
+	}
 }
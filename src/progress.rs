@@ -0,0 +1,312 @@
+//! Learner progress: the pages visited, how far down they were read and the best result of
+//! each quiz.
+//!
+//! Progress is kept in `localStorage` as JSON, so it works for served and `file://` builds
+//! alike, and the same JSON is used to export it to a file and import it on another device.
+
+use std::collections::BTreeMap;
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::content::{Page, Quiz};
+use crate::storage;
+
+/// Storage key of the learner's progress.
+const PROGRESS_KEY: &str = "progress";
+
+/// Scroll depth, in percent, from which a page counts as read.
+pub const READ_DEPTH: u8 = 90;
+
+/// Steps, in percent, in which reading depth is saved, so that scrolling rewrites storage a
+/// few times per page rather than on every small gain. [`READ_DEPTH`] is a whole step.
+const DEPTH_STEP: u8 = 10;
+
+/// Rounds a scroll depth down to a whole [`DEPTH_STEP`].
+fn coarse_depth(depth: u8) -> u8 {
+	depth - depth % DEPTH_STEP
+}
+
+/// Progress on one page.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageProgress {
+	pub visited: bool,
+	/// Furthest scroll depth reached, in percent of the page height.
+	pub depth: u8,
+}
+
+/// Best score of a quiz.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuizResult {
+	pub score: usize,
+	pub total: usize,
+}
+
+impl QuizResult {
+	/// Whether the score reaches the quiz's pass mark.
+	pub fn passed(self, quiz: &Quiz) -> bool {
+		self.total > 0 && self.score * 100 >= quiz.pass as usize * self.total
+	}
+}
+
+/// Everything recorded about a learner, keyed by page slug.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progress {
+	pub pages: BTreeMap<String, PageProgress>,
+	/// Best result of each quiz, keyed `<page slug>#<quiz id>`.
+	pub quizzes: BTreeMap<String, QuizResult>,
+}
+
+fn quiz_key(page: &Page, quiz: &Quiz) -> String {
+	format!("{}#{}", page.slug, quiz.id)
+}
+
+impl Progress {
+	/// Parses exported or stored progress.
+	pub fn from_json(json: &str) -> Result<Self, String> {
+		serde_json::from_str(json).map_err(|err| err.to_string())
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).unwrap_or_default()
+	}
+
+	pub fn page(&self, page: &Page) -> Option<&PageProgress> {
+		self.pages.get(page.slug)
+	}
+
+	pub fn quiz(&self, page: &Page, quiz: &Quiz) -> Option<QuizResult> {
+		self.quizzes.get(&quiz_key(page, quiz)).copied()
+	}
+
+	pub fn quiz_passed(&self, page: &Page, quiz: &Quiz) -> bool {
+		self.quiz(page, quiz)
+			.is_some_and(|result| result.passed(quiz))
+	}
+
+	/// Whether `page` was read to [`READ_DEPTH`] and all its quizzes passed.
+	pub fn is_complete(&self, page: &Page) -> bool {
+		self.page(page)
+			.is_some_and(|progress| progress.visited && progress.depth >= READ_DEPTH)
+			&& page.quizzes.iter().all(|quiz| self.quiz_passed(page, quiz))
+	}
+
+	/// Adds `other` to this progress, keeping the furthest depth and best quiz results.
+	pub fn merge(&mut self, other: Progress) {
+		for (slug, theirs) in other.pages {
+			let ours = self.pages.entry(slug).or_default();
+			ours.visited |= theirs.visited;
+			ours.depth = ours.depth.max(theirs.depth);
+		}
+		for (key, theirs) in other.quizzes {
+			let ours = self.quizzes.entry(key).or_insert(theirs);
+			if theirs.score > ours.score {
+				*ours = theirs;
+			}
+		}
+	}
+
+	/// Share of `pages` that are complete, in percent rounded down.
+	pub fn percent_complete(&self, pages: &[&Page]) -> usize {
+		if pages.is_empty() {
+			return 0;
+		}
+		let complete = pages.iter().filter(|page| self.is_complete(page)).count();
+		complete * 100 / pages.len()
+	}
+}
+
+/// Learner progress, provided to the whole app by [`provide_progress`].
+#[derive(Clone, Copy)]
+pub struct ProgressContext(RwSignal<Progress>);
+
+impl ProgressContext {
+	/// Reads the progress, tracked.
+	pub fn with<T>(&self, f: impl FnOnce(&Progress) -> T) -> T {
+		self.0.with(f)
+	}
+
+	/// Changes the progress of `page` if `change` returns `true`, so that revisits and
+	/// small scrolls do not rewrite storage.
+	fn update_page(&self, page: &Page, change: impl FnOnce(&mut PageProgress) -> bool) {
+		let mut progress = self
+			.0
+			.with_untracked(|p| p.page(page).cloned().unwrap_or_default());
+		if change(&mut progress) {
+			self.0.update(|p| {
+				p.pages.insert(page.slug.to_string(), progress);
+			});
+		}
+	}
+
+	pub fn visit(&self, page: &Page) {
+		self.update_page(page, |progress| {
+			!std::mem::replace(&mut progress.visited, true)
+		});
+	}
+
+	/// Records reading `page` down to `depth` percent, rounded down to a [`DEPTH_STEP`], if
+	/// further than before.
+	pub fn record_depth(&self, page: &Page, depth: u8) {
+		let depth = coarse_depth(depth);
+		self.update_page(page, |progress| {
+			let deeper = depth > progress.depth;
+			progress.depth = progress.depth.max(depth);
+			deeper
+		});
+	}
+
+	/// Records a quiz result, keeping the best one.
+	pub fn record_quiz(&self, page: &Page, quiz: &Quiz, result: QuizResult) {
+		let best = self.0.with_untracked(|p| p.quiz(page, quiz));
+		if best.is_none_or(|best| result.score > best.score) {
+			self.0.update(|p| {
+				p.quizzes.insert(quiz_key(page, quiz), result);
+			});
+		}
+	}
+
+	/// Merges progress imported from a file into the current progress.
+	pub fn import(&self, progress: Progress) {
+		self.0.update(|current| current.merge(progress));
+	}
+}
+
+/// Returns the progress context provided by [`provide_progress`].
+pub fn use_progress() -> ProgressContext {
+	expect_context()
+}
+
+/// Loads the saved progress, keeps storage in sync with it and provides a
+/// [`ProgressContext`]. Call once, from the root component.
+pub fn provide_progress() {
+	let progress = storage::load(PROGRESS_KEY)
+		.and_then(|json| Progress::from_json(&json).ok())
+		.unwrap_or_default();
+	let progress = RwSignal::new(progress);
+	Effect::new(move |_| {
+		progress.with(|progress| storage::save(PROGRESS_KEY, &progress.to_json()))
+	});
+	provide_context(ProgressContext(progress));
+}
+
+/// How far down the document the bottom of the viewport is, in percent. Pages shorter
+/// than the viewport are fully read.
+pub fn scroll_depth() -> u8 {
+	let window = window();
+	let bottom = window.scroll_y().unwrap_or_default()
+		+ window
+			.inner_height()
+			.ok()
+			.and_then(|height| height.as_f64())
+			.unwrap_or_default();
+	let height = document()
+		.document_element()
+		.map_or(0, |root| root.scroll_height()) as f64;
+	if height <= 0.0 {
+		return 100;
+	}
+	(bottom / height * 100.0).clamp(0.0, 100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn page(visited: bool, depth: u8) -> PageProgress {
+		PageProgress { visited, depth }
+	}
+
+	fn progress(pages: &[(&str, PageProgress)], quizzes: &[(&str, usize, usize)]) -> Progress {
+		Progress {
+			pages: pages
+				.iter()
+				.map(|(slug, page)| (slug.to_string(), page.clone()))
+				.collect(),
+			quizzes: quizzes
+				.iter()
+				.map(|(key, score, total)| {
+					let result = QuizResult {
+						score: *score,
+						total: *total,
+					};
+					(key.to_string(), result)
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn merge_keeps_the_furthest_reading_and_best_scores() {
+		let mut ours = progress(
+			&[("intro", page(true, 40)), ("borrowing", page(true, 95))],
+			&[("intro#q", 1, 4), ("borrowing#q", 3, 3)],
+		);
+		let theirs = progress(
+			&[("intro", page(false, 80)), ("lifetimes", page(true, 10))],
+			&[
+				("intro#q", 3, 4),
+				("borrowing#q", 2, 3),
+				("lifetimes#q", 0, 2),
+			],
+		);
+		ours.merge(theirs);
+		assert_eq!(
+			ours,
+			progress(
+				&[
+					("borrowing", page(true, 95)),
+					("intro", page(true, 80)),
+					("lifetimes", page(true, 10)),
+				],
+				&[
+					("borrowing#q", 3, 3),
+					("intro#q", 3, 4),
+					("lifetimes#q", 0, 2)
+				],
+			)
+		);
+	}
+
+	#[test]
+	fn depth_is_saved_in_whole_steps() {
+		assert_eq!(coarse_depth(0), 0);
+		assert_eq!(coarse_depth(9), 0);
+		assert_eq!(coarse_depth(89), 80);
+		assert_eq!(coarse_depth(READ_DEPTH), READ_DEPTH);
+		assert_eq!(coarse_depth(100), 100);
+	}
+
+	#[test]
+	fn json_round_trips() {
+		let progress = progress(&[("intro", page(true, 90))], &[("intro#q", 2, 3)]);
+		assert_eq!(Progress::from_json(&progress.to_json()), Ok(progress));
+	}
+
+	#[test]
+	fn missing_fields_take_defaults() {
+		let parsed = Progress::from_json(r#"{ "pages": { "intro": { "depth": 50 } } }"#);
+		assert_eq!(parsed, Ok(progress(&[("intro", page(false, 50))], &[])));
+	}
+
+	#[test]
+	fn invalid_json_is_rejected() {
+		assert!(Progress::from_json("not json").is_err());
+		assert!(Progress::from_json(r#"{ "pages": [] }"#).is_err());
+	}
+
+	#[test]
+	fn quizzes_pass_at_their_pass_mark() {
+		let quiz = Quiz {
+			id: "q",
+			pass: 75,
+			questions: &[],
+		};
+		let result = |score, total| QuizResult { score, total };
+		assert!(result(3, 4).passed(&quiz));
+		assert!(!result(2, 4).passed(&quiz));
+		assert!(!result(0, 0).passed(&quiz));
+	}
+}
@@ -0,0 +1,83 @@
+//! Subsequence matching for short labels such as page titles and headings.
+
+/// Bonus for a match right after the previous one.
+const CONSECUTIVE_BONUS: u32 = 8;
+
+/// Bonus for a match at the start of a word.
+const WORD_START_BONUS: u32 = 6;
+
+/// Bonus when the label starts with the query.
+const PREFIX_BONUS: u32 = 12;
+
+/// Scores how well `query` matches `label`, ignoring case.
+///
+/// Every character of the query must appear in the label in order; matches that are
+/// consecutive or start words score higher. Returns `None` if the label does not match.
+pub fn fuzzy_score(query: &str, label: &str) -> Option<u32> {
+	let mut query = query
+		.chars()
+		.filter(|c| !c.is_whitespace())
+		.flat_map(char::to_lowercase)
+		.peekable();
+	let mut score = 0;
+	let mut previous_match = None;
+	let mut previous_char = ' ';
+
+	for (index, c) in label.chars().flat_map(char::to_lowercase).enumerate() {
+		let Some(&wanted) = query.peek() else {
+			break;
+		};
+		if c == wanted {
+			query.next();
+			score += 1;
+			if previous_match.is_some_and(|previous| previous + 1 == index) {
+				score += CONSECUTIVE_BONUS;
+			}
+			if !previous_char.is_alphanumeric() {
+				score += WORD_START_BONUS;
+				if index == 0 {
+					score += PREFIX_BONUS;
+				}
+			}
+			previous_match = Some(index);
+		}
+		previous_char = c;
+	}
+
+	query.peek().is_none().then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn requires_every_character_in_order() {
+		assert!(fuzzy_score("brw", "Borrowing").is_some());
+		assert!(fuzzy_score("wb", "Borrowing").is_none());
+		assert!(fuzzy_score("borrowing!", "Borrowing").is_none());
+	}
+
+	#[test]
+	fn ignores_case_and_whitespace() {
+		assert_eq!(
+			fuzzy_score("BOR ROW", "borrow"),
+			fuzzy_score("borrow", "Borrow")
+		);
+	}
+
+	#[test]
+	fn ranks_prefixes_then_word_starts_then_inner_matches() {
+		let prefix = fuzzy_score("bor", "Borrowing").unwrap();
+		let word_start = fuzzy_score("bor", "Rust borrowing").unwrap();
+		let inner = fuzzy_score("bor", "Arbor").unwrap();
+		assert!(prefix > word_start && word_start > inner);
+	}
+
+	#[test]
+	fn ranks_consecutive_matches_above_scattered_ones() {
+		assert!(
+			fuzzy_score("own", "ownership").unwrap() > fuzzy_score("own", "ol wide n").unwrap()
+		);
+	}
+}
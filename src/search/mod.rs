@@ -0,0 +1,242 @@
+//! Client-side full-text search over the index generated by the build script,
+//! and fuzzy matching for the command palette.
+
+use std::collections::HashMap;
+
+use crate::content::{Page, PAGES};
+
+mod fuzzy;
+mod tokenize;
+
+pub use self::fuzzy::fuzzy_score;
+use self::tokenize::{normalize, stem, words};
+
+/// A heading-delimited part of a page.
+pub struct Section {
+	/// Index of the page in `PAGES`.
+	pub page: usize,
+	/// Heading id, or empty for the top of the page.
+	pub anchor: &'static str,
+	pub heading: &'static str,
+	pub text: &'static str,
+}
+
+include!(concat!(env!("OUT_DIR"), "/search_index.rs"));
+
+/// Characters of context shown before the first match in a snippet.
+const SNIPPET_LEAD: usize = 40;
+
+/// Total length of a snippet in characters.
+const SNIPPET_LEN: usize = 140;
+
+/// A section matching every term of a query.
+#[derive(Clone, Copy, PartialEq)]
+pub struct SearchResult {
+	section: usize,
+	pub score: u32,
+}
+
+impl SearchResult {
+	pub fn section(&self) -> &'static Section {
+		&SECTIONS[self.section]
+	}
+
+	pub fn page(&self) -> &'static Page {
+		&PAGES[self.section().page]
+	}
+
+	/// Route to the page, with the section's heading as fragment.
+	pub fn href(&self) -> String {
+		let section = self.section();
+		let slug = self.page().slug;
+		if section.anchor.is_empty() {
+			format!("/docs/{slug}")
+		} else {
+			format!("/docs/{slug}#{}", section.anchor)
+		}
+	}
+}
+
+/// Normalized query words; the last one is matched as a prefix while still being typed.
+fn query_words(query: &str) -> Vec<String> {
+	words(query)
+		.filter_map(|(_, word)| normalize(word))
+		.collect()
+}
+
+/// Whether `term` is a stem the partially typed `word` has grown past,
+/// e.g. `borrow` for `borrowi`, allowing for a few extra characters.
+fn grown_past(word: &str, term: &str) -> bool {
+	term.len() < word.len()
+		&& term.len() >= word.len().saturating_sub(3).max(3)
+		&& word.starts_with(term)
+}
+
+/// Weighted section postings for every indexed term matching `word`.
+fn postings(word: &str, prefix: bool) -> HashMap<u32, u32> {
+	let stemmed = stem(word);
+	let mut matches = HashMap::new();
+	let mut add = |postings: &[(u32, u32)]| {
+		for &(section, weight) in postings {
+			*matches.entry(section).or_default() += weight;
+		}
+	};
+
+	if prefix {
+		// Terms extending what has been typed so far...
+		let start = TERMS.partition_point(|(term, _)| *term < stemmed.as_str());
+		TERMS[start..]
+			.iter()
+			.take_while(|(term, _)| term.starts_with(&stemmed))
+			.for_each(|(_, postings)| add(postings));
+		// ...and stems the typed word has already grown past.
+		for len in (1..word.len()).filter(|&len| word.is_char_boundary(len)) {
+			let term = &word[..len];
+			if !grown_past(word, term) || term.starts_with(&stemmed) {
+				continue;
+			}
+			if let Ok(index) = TERMS.binary_search_by(|(candidate, _)| (*candidate).cmp(term)) {
+				add(TERMS[index].1);
+			}
+		}
+	} else if let Ok(index) = TERMS.binary_search_by(|(term, _)| (*term).cmp(stemmed.as_str())) {
+		add(TERMS[index].1);
+	}
+	matches
+}
+
+/// Returns up to `limit` sections containing every query word, best matches first.
+pub fn search(query: &str, limit: usize) -> Vec<SearchResult> {
+	let words = query_words(query);
+	let typing = !query.ends_with(char::is_whitespace);
+
+	let mut scores: Option<HashMap<u32, u32>> = None;
+	for (i, word) in words.iter().enumerate() {
+		let matches = postings(word, typing && i == words.len() - 1);
+		scores = Some(match scores {
+			None => matches,
+			Some(scores) => scores
+				.into_iter()
+				.filter_map(|(section, score)| {
+					matches
+						.get(&section)
+						.map(|weight| (section, score + weight))
+				})
+				.collect(),
+		});
+	}
+
+	let mut results: Vec<SearchResult> = scores
+		.unwrap_or_default()
+		.into_iter()
+		.map(|(section, score)| SearchResult {
+			section: section as usize,
+			score,
+		})
+		.collect();
+	results.sort_by(|a, b| b.score.cmp(&a.score).then(a.section.cmp(&b.section)));
+	results.truncate(limit);
+	results
+}
+
+/// Cuts a snippet of `text` around the first query match.
+/// Returns the snippet as segments, flagging those that match the query.
+pub fn snippet(text: &'static str, query: &str) -> Vec<(&'static str, bool)> {
+	let stems: Vec<String> = query_words(query).iter().map(|word| stem(word)).collect();
+	let matches: Vec<(usize, usize)> = words(text)
+		.filter(|(_, word)| {
+			normalize(word).is_some_and(|word| {
+				let stemmed = stem(&word);
+				stems.iter().any(|query| {
+					stemmed.starts_with(query.as_str())
+						|| word.starts_with(query.as_str())
+						|| grown_past(query, &stemmed)
+				})
+			})
+		})
+		.map(|(offset, word)| (offset, offset + word.len()))
+		.collect();
+
+	// Window of SNIPPET_LEN characters starting a little before the first match.
+	let first = matches.first().map_or(0, |&(start, _)| start);
+	let lead_start = text[..first]
+		.char_indices()
+		.rev()
+		.nth(SNIPPET_LEAD - 1)
+		.filter(|_| text.chars().nth(SNIPPET_LEN).is_some());
+	let start = match lead_start {
+		// Skip the partial word at the start of the window.
+		Some((lead_start, _)) => text[lead_start..first]
+			.find(' ')
+			.map_or(lead_start, |space| lead_start + space + 1),
+		// Short texts and early matches are shown from the start.
+		None => 0,
+	};
+	let end = text[start..]
+		.char_indices()
+		.nth(SNIPPET_LEN)
+		.map_or(text.len(), |(index, _)| start + index);
+
+	let mut segments = Vec::new();
+	let mut cursor = start;
+	for &(match_start, match_end) in &matches {
+		if match_start < start {
+			continue;
+		}
+		if match_end > end {
+			break;
+		}
+		if cursor < match_start {
+			segments.push((&text[cursor..match_start], false));
+		}
+		segments.push((&text[match_start..match_end], true));
+		cursor = match_end;
+	}
+	if cursor < end {
+		segments.push((&text[cursor..end], false));
+	}
+	segments
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn grown_past_allows_a_few_extra_characters() {
+		assert!(grown_past("borrowi", "borrow"));
+		assert!(!grown_past("borrow", "borrow"));
+		assert!(!grown_past("borrowings", "bor"));
+		assert!(!grown_past("borrowi", "barrow"));
+	}
+
+	#[test]
+	fn short_snippets_flag_matches() {
+		assert_eq!(
+			snippet("Borrowing rules for references", "borrow"),
+			[("Borrowing", true), (" rules for references", false)]
+		);
+		assert_eq!(
+			snippet("References are borrowed", "borrowing"),
+			[("References are ", false), ("borrowed", true)]
+		);
+	}
+
+	#[test]
+	fn snippets_without_matches_start_at_the_text() {
+		assert_eq!(snippet("Lifetimes", "borrow"), [("Lifetimes", false)]);
+	}
+
+	#[test]
+	fn long_snippets_start_shortly_before_the_first_match() {
+		let text: &'static str = Box::leak(
+			format!("{}borrow {}", "word ".repeat(30), "tail ".repeat(30)).into_boxed_str(),
+		);
+		let segments = snippet(text, "borrow");
+		let (before, _) = segments[0];
+		assert!(before.starts_with("word") && before.len() <= SNIPPET_LEAD);
+		assert_eq!(segments[1], ("borrow", true));
+		let length: usize = segments.iter().map(|(text, _)| text.chars().count()).sum();
+		assert!(length <= SNIPPET_LEN);
+	}
+}
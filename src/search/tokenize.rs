@@ -0,0 +1,153 @@
+//! Tokenizer and stemmer shared by the build-time search indexer and in-app queries.
+//!
+//! The build script includes this file by path, so it must only depend on `std`.
+
+/// Common English words left out of the index.
+const STOP_WORDS: &[&str] = &[
+	"a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on",
+	"or", "that", "the", "this", "to", "was", "with",
+];
+
+/// Splits text into alphanumeric words, yielding each word's byte offset.
+pub fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+	text.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Lowercases a word, returning `None` for stop words and single characters.
+pub fn normalize(word: &str) -> Option<String> {
+	let word = word.to_lowercase();
+	(word.chars().nth(1).is_some() && !STOP_WORDS.contains(&word.as_str())).then_some(word)
+}
+
+/// Reduces a lowercase English word to its stem using steps 1a to 1c of the Porter
+/// algorithm, which covers plurals and `-ed`/`-ing` forms. Non-ASCII words are kept as is.
+pub fn stem(word: &str) -> String {
+	if word.len() <= 2 || !word.is_ascii() {
+		return word.to_string();
+	}
+	let mut w = word.as_bytes().to_vec();
+
+	// Step 1a: plurals.
+	if w.ends_with(b"sses") || w.ends_with(b"ies") {
+		w.truncate(w.len() - 2);
+	} else if !w.ends_with(b"ss") && w.ends_with(b"s") {
+		w.pop();
+	}
+
+	// Step 1b: past tense and gerunds.
+	if w.ends_with(b"eed") {
+		if measure(&w[..w.len() - 3]) > 0 {
+			w.pop();
+		}
+	} else if let Some(suffix) = [&b"ed"[..], b"ing"]
+		.into_iter()
+		.find(|suffix| w.ends_with(suffix) && has_vowel(&w[..w.len() - suffix.len()]))
+	{
+		w.truncate(w.len() - suffix.len());
+		if w.ends_with(b"at") || w.ends_with(b"bl") || w.ends_with(b"iz") {
+			w.push(b'e');
+		} else if ends_double_consonant(&w) && !matches!(w.last(), Some(b'l' | b's' | b'z')) {
+			w.pop();
+		} else if measure(&w) == 1 && ends_cvc(&w) {
+			w.push(b'e');
+		}
+	}
+
+	// Step 1c: terminal `y` after a vowel-bearing stem.
+	if w.ends_with(b"y") && has_vowel(&w[..w.len() - 1]) {
+		w.pop();
+		w.push(b'i');
+	}
+
+	String::from_utf8(w).unwrap_or_else(|_| word.to_string())
+}
+
+fn is_consonant(w: &[u8], i: usize) -> bool {
+	match w[i] {
+		b'a' | b'e' | b'i' | b'o' | b'u' => false,
+		b'y' => i == 0 || !is_consonant(w, i - 1),
+		_ => true,
+	}
+}
+
+/// Number of vowel-consonant sequences in `w`.
+fn measure(w: &[u8]) -> usize {
+	let mut count = 0;
+	let mut prev_vowel = false;
+	for i in 0..w.len() {
+		let consonant = is_consonant(w, i);
+		if consonant && prev_vowel {
+			count += 1;
+		}
+		prev_vowel = !consonant;
+	}
+	count
+}
+
+fn has_vowel(w: &[u8]) -> bool {
+	(0..w.len()).any(|i| !is_consonant(w, i))
+}
+
+fn ends_double_consonant(w: &[u8]) -> bool {
+	let n = w.len();
+	n >= 2 && w[n - 1] == w[n - 2] && is_consonant(w, n - 1)
+}
+
+/// Whether `w` ends consonant-vowel-consonant, with the last not `w`, `x` or `y`.
+fn ends_cvc(w: &[u8]) -> bool {
+	let n = w.len();
+	n >= 3
+		&& is_consonant(w, n - 3)
+		&& !is_consonant(w, n - 2)
+		&& is_consonant(w, n - 1)
+		&& !matches!(w[n - 1], b'w' | b'x' | b'y')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn splits_words_with_byte_offsets() {
+		let found: Vec<_> = words("Hello, wörld 42!").collect();
+		assert_eq!(found, [(0, "Hello"), (7, "wörld"), (14, "42")]);
+	}
+
+	#[test]
+	fn normalizes_and_drops_stop_words() {
+		assert_eq!(normalize("Rust").as_deref(), Some("rust"));
+		assert_eq!(normalize("The"), None);
+		assert_eq!(normalize("x"), None);
+	}
+
+	#[test]
+	fn stems_plurals_and_verb_forms() {
+		for (word, stemmed) in [
+			("caresses", "caress"),
+			("ponies", "poni"),
+			("cats", "cat"),
+			("feed", "feed"),
+			("agreed", "agree"),
+			("plastered", "plaster"),
+			("motoring", "motor"),
+			("conflated", "conflate"),
+			("hopping", "hop"),
+			("falling", "fall"),
+			("filing", "file"),
+			("happy", "happi"),
+			("sky", "sky"),
+			("borrowing", "borrow"),
+			("borrowed", "borrow"),
+		] {
+			assert_eq!(stem(word), stemmed, "stem of {word}");
+		}
+	}
+
+	#[test]
+	fn keeps_short_and_non_ascii_words() {
+		assert_eq!(stem("is"), "is");
+		assert_eq!(stem("größen"), "größen");
+	}
+}
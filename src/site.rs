@@ -0,0 +1,25 @@
+//! Site-wide settings from `bezel.toml`, used for document titles, meta tags and the sidebar.
+//!
+//! The build script generates `SITE_NAME`, `TITLE_TEMPLATE` (with `{title}` and `{site}`
+//! placeholders), `SITE_URL` (`site.url`, `None` unless configured), `CATEGORY_ORDER` and
+//! `DEFAULT_CATEGORY`, the category of pages that set none.
+
+include!(concat!(env!("OUT_DIR"), "/site.rs"));
+
+/// Token colours of highlighted code blocks for the light and dark themes.
+pub const HIGHLIGHT_CSS: &str = include_str!(concat!(env!("OUT_DIR"), "/highlight.css"));
+
+/// Formats a page title with [`TITLE_TEMPLATE`]; the site name on its own is left as is.
+pub fn document_title(title: String) -> String {
+	if title.is_empty() || title == SITE_NAME {
+		return SITE_NAME.to_string();
+	}
+	TITLE_TEMPLATE
+		.replace("{title}", &title)
+		.replace("{site}", SITE_NAME)
+}
+
+/// Absolute URL of the page at `slug`, if [`SITE_URL`] is set.
+pub fn page_url(slug: &str) -> Option<String> {
+	SITE_URL.map(|url| format!("{}/docs/{slug}", url.trim_end_matches('/')))
+}
@@ -0,0 +1,28 @@
+//! Reader preferences kept in `localStorage`.
+//!
+//! Keys are namespaced with `bezel.` so several sites served from the same origin,
+//! or opened from `file://`, don't clash. Storage that is unavailable or full is
+//! silently ignored; preferences then last for the session only.
+
+use leptos::prelude::window;
+
+const PREFIX: &str = "bezel.";
+
+fn local_storage() -> Option<web_sys::Storage> {
+	window().local_storage().ok().flatten()
+}
+
+/// Returns the stored value of `key`, if any.
+pub fn load(key: &str) -> Option<String> {
+	local_storage()?
+		.get_item(&format!("{PREFIX}{key}"))
+		.ok()
+		.flatten()
+}
+
+/// Stores `value` under `key`.
+pub fn save(key: &str, value: &str) {
+	if let Some(storage) = local_storage() {
+		let _ = storage.set_item(&format!("{PREFIX}{key}"), value);
+	}
+}
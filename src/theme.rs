@@ -0,0 +1,146 @@
+//! Light and dark colour themes, chosen by the reader or following the system.
+//!
+//! The resolved theme is written to `data-theme` on the root element, which the
+//! stylesheet keys its colours on. `resources/*.html` apply the saved choice with a
+//! small inline script before the app loads, so the page never flashes the wrong theme.
+
+use leptos::prelude::*;
+use leptos::wasm_bindgen::closure::Closure;
+use leptos::wasm_bindgen::JsCast;
+
+use crate::storage;
+
+/// Storage key of the reader's choice; the inline script in `resources/*.html` reads it too.
+const THEME_KEY: &str = "theme";
+
+const DARK_QUERY: &str = "(prefers-color-scheme: dark)";
+
+/// Theme picked by the reader.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeChoice {
+	Light,
+	Dark,
+	/// Follow the operating system's preference.
+	#[default]
+	System,
+}
+
+impl ThemeChoice {
+	fn as_str(self) -> &'static str {
+		match self {
+			ThemeChoice::Light => "light",
+			ThemeChoice::Dark => "dark",
+			ThemeChoice::System => "system",
+		}
+	}
+
+	fn parse(value: &str) -> Option<Self> {
+		match value {
+			"light" => Some(ThemeChoice::Light),
+			"dark" => Some(ThemeChoice::Dark),
+			"system" => Some(ThemeChoice::System),
+			_ => None,
+		}
+	}
+
+	/// The choice after this one when cycling light → dark → system.
+	pub fn next(self) -> Self {
+		match self {
+			ThemeChoice::Light => ThemeChoice::Dark,
+			ThemeChoice::Dark => ThemeChoice::System,
+			ThemeChoice::System => ThemeChoice::Light,
+		}
+	}
+
+	pub fn label(self) -> &'static str {
+		match self {
+			ThemeChoice::Light => "Light",
+			ThemeChoice::Dark => "Dark",
+			ThemeChoice::System => "System",
+		}
+	}
+}
+
+/// Theme actually applied to the page.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+	Light,
+	Dark,
+}
+
+impl Theme {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Theme::Light => "light",
+			Theme::Dark => "dark",
+		}
+	}
+}
+
+/// Current theme, provided to the whole app by [`provide_theme`].
+#[derive(Clone, Copy)]
+pub struct ThemeContext {
+	choice: RwSignal<ThemeChoice>,
+	theme: Memo<Theme>,
+}
+
+impl ThemeContext {
+	/// The reader's choice, tracked.
+	pub fn choice(&self) -> ThemeChoice {
+		self.choice.get()
+	}
+
+	/// The applied theme, tracked; changes with the system preference under `System`.
+	pub fn theme(&self) -> Theme {
+		self.theme.get()
+	}
+
+	/// Switches to the next choice in light → dark → system order.
+	pub fn cycle(&self) {
+		self.choice.update(|choice| *choice = choice.next());
+	}
+}
+
+/// Returns the theme context provided by [`provide_theme`].
+pub fn use_theme() -> ThemeContext {
+	expect_context()
+}
+
+/// Loads the saved theme choice, keeps `data-theme` and storage in sync with it and
+/// provides a [`ThemeContext`]. Call once, from the root component.
+pub fn provide_theme() {
+	let choice = RwSignal::new(
+		storage::load(THEME_KEY)
+			.as_deref()
+			.and_then(ThemeChoice::parse)
+			.unwrap_or_default(),
+	);
+
+	let query = window().match_media(DARK_QUERY).ok().flatten();
+	let system_dark = RwSignal::new(query.as_ref().is_some_and(|query| query.matches()));
+	if let Some(query) = query {
+		let listener = Closure::<dyn Fn()>::new({
+			let query = query.clone();
+			move || system_dark.set(query.matches())
+		});
+		let _ = query.add_event_listener_with_callback("change", listener.as_ref().unchecked_ref());
+		// The root component lives as long as the page, so the listener does too.
+		listener.forget();
+	}
+
+	let theme = Memo::new(move |_| match choice.get() {
+		ThemeChoice::Light => Theme::Light,
+		ThemeChoice::Dark => Theme::Dark,
+		ThemeChoice::System if system_dark.get() => Theme::Dark,
+		ThemeChoice::System => Theme::Light,
+	});
+
+	Effect::new(move |_| {
+		if let Some(root) = document().document_element() {
+			let _ = root.set_attribute("data-theme", theme.get().as_str());
+		}
+	});
+	Effect::new(move |_| storage::save(THEME_KEY, choice.get().as_str()));
+
+	provide_context(ThemeContext { choice, theme });
+}